@@ -0,0 +1,50 @@
+//! Embeds the campaign: reads `assets/levels/campaign.toml` and writes the list of
+//! level files it names, so `core::campaign` always matches the manifest (including
+//! levels the editor appends).
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR");
+    let levels_dir = Path::new(&manifest_dir).join("assets/levels");
+    let manifest_path = levels_dir.join("campaign.toml");
+    println!("cargo:rerun-if-changed={}", levels_dir.display());
+    println!("cargo:rerun-if-changed={}", manifest_path.display());
+
+    let manifest = fs::read_to_string(&manifest_path)
+        .unwrap_or_else(|err| panic!("cannot read {}: {err}", manifest_path.display()));
+    let table: toml::Table = manifest
+        .parse()
+        .unwrap_or_else(|err| panic!("{} is not valid TOML: {err}", manifest_path.display()));
+    let names: Vec<&str> = table
+        .get("levels")
+        .and_then(toml::Value::as_array)
+        .map(|levels| levels.iter().filter_map(toml::Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut entries = String::new();
+    for name in names {
+        let path = levels_dir.join(name);
+        if !path.is_file() {
+            panic!(
+                "{} lists '{name}', which does not exist",
+                manifest_path.display()
+            );
+        }
+        println!("cargo:rerun-if-changed={}", path.display());
+        entries.push_str(&format!(
+            "    ({name:?}, include_str!({:?})),\n",
+            path.display().to_string()
+        ));
+    }
+
+    let out_path =
+        Path::new(&env::var("OUT_DIR").expect("cargo sets OUT_DIR")).join("builtin_levels.rs");
+    fs::write(
+        &out_path,
+        format!("const BUILTIN_LEVEL_FILES: &[(&str, &str)] = &[\n{entries}];\n"),
+    )
+    .unwrap_or_else(|err| panic!("cannot write {}: {err}", out_path.display()));
+}
@@ -5,10 +5,11 @@ use crate::constants::{
     RIGHT_MARGIN, TOP_MARGIN, UI_FONT_PATH,
 };
 use crate::controls::{FrameActions, draw_control_panel, hotkey_actions};
+use crate::editor::run_editor_frame;
 use crate::gameplay::{apply_actions, apply_keyboard_adjustments, step_active_flight};
 use crate::hud::draw_hud;
 use crate::input::{update_launch_editor, update_surface_editor};
-use crate::model::AppScene;
+use crate::model::{AppScene, load_campaign};
 use crate::physics::{compute_world_window, simulate_prediction, world_to_screen};
 use crate::render::{
     draw_axis_tick_labels, draw_grid, draw_launch_editor, draw_level_objects, draw_path,
@@ -36,7 +37,8 @@ pub(crate) async fn run() {
         }
     };
 
-    let mut state = AppRuntime::new();
+    let (levels, level_load_errors) = load_campaign().await;
+    let mut state = AppRuntime::new(levels, level_load_errors);
 
     loop {
         let frame_dt = get_frame_time();
@@ -44,15 +46,30 @@ pub(crate) async fn run() {
         let screen_h = screen_height();
 
         if state.scene == AppScene::Title {
-            if draw_title_screen(screen_w, screen_h, ui_font.as_ref()) {
-                state.scene = AppScene::Game;
-                state.game.reset();
-                state.set_loaded_status();
+            match draw_title_screen(
+                screen_w,
+                screen_h,
+                &state.level_load_errors,
+                ui_font.as_ref(),
+            ) {
+                Some(AppScene::Editor) => state.open_editor(),
+                Some(_) => {
+                    state.scene = AppScene::Game;
+                    state.game.reset();
+                    state.set_loaded_status();
+                }
+                None => {}
             }
             next_frame().await;
             continue;
         }
 
+        if state.scene == AppScene::Editor {
+            run_editor_frame(&mut state, screen_w, screen_h, ui_font.as_ref());
+            next_frame().await;
+            continue;
+        }
+
         let left = LEFT_MARGIN;
         let right = screen_w - RIGHT_MARGIN;
         let top = TOP_MARGIN;
@@ -65,8 +82,10 @@ pub(crate) async fn run() {
 
         apply_keyboard_adjustments(&mut state, frame_dt);
         step_active_flight(&mut state, frame_dt);
+        state.advance_hint_search();
 
-        let mut prediction = simulate_prediction(state.config, state.current_level());
+        let mut prediction =
+            simulate_prediction(state.config, state.current_level(), state.integrator);
         let (mut world_max_x, mut world_max_y) = compute_world_window(
             state.current_level(),
             state.config,
@@ -99,7 +118,7 @@ pub(crate) async fn run() {
             bottom,
         );
         if launch_drag_changed {
-            prediction = simulate_prediction(state.config, state.current_level());
+            prediction = simulate_prediction(state.config, state.current_level(), state.integrator);
             let window = compute_world_window(
                 state.current_level(),
                 state.config,
@@ -123,8 +142,12 @@ pub(crate) async fn run() {
             bottom,
         );
 
+        if state.surface_editor.is_dragging() {
+            state.clear_hint();
+        }
+
         if show_surface_handles || state.surface_editor.is_dragging() {
-            prediction = simulate_prediction(state.config, state.current_level());
+            prediction = simulate_prediction(state.config, state.current_level(), state.integrator);
             let window = compute_world_window(
                 state.current_level(),
                 state.config,
@@ -164,6 +187,8 @@ pub(crate) async fn run() {
             bottom,
             show_surface_handles,
             &state.surface_editor,
+            state.game.shot.map_or(0.0, |shot| shot.elapsed_s),
+            (state.show_preview && state.game.shot.is_none()).then_some(prediction.flight_time_s),
         );
         let launch_screen_after = world_to_screen(
             vec2(0.0, state.config.height_m.max(0.0)),
@@ -190,6 +215,20 @@ pub(crate) async fn run() {
             );
         }
 
+        if let Some(hint_arc) = &state.hint_arc {
+            draw_path(
+                &hint_arc.points,
+                world_max_x,
+                world_max_y,
+                left,
+                right,
+                top,
+                bottom,
+                2.5,
+                Color::from_rgba(34, 160, 90, 120),
+            );
+        }
+
         if !state.game.trail.is_empty() {
             draw_path(
                 &state.game.trail,
@@ -10,6 +10,8 @@ pub(crate) struct FrameActions {
     pub(crate) reset: bool,
     pub(crate) prev_level: bool,
     pub(crate) next_level: bool,
+    pub(crate) hint: bool,
+    pub(crate) edit: bool,
 }
 
 impl FrameActions {
@@ -19,6 +21,8 @@ impl FrameActions {
             reset: self.reset || other.reset,
             prev_level: self.prev_level || other.prev_level,
             next_level: self.next_level || other.next_level,
+            hint: self.hint || other.hint,
+            edit: self.edit || other.edit,
         }
     }
 }
@@ -29,18 +33,20 @@ pub(crate) fn hotkey_actions() -> FrameActions {
         reset: is_key_pressed(KeyCode::R),
         prev_level: is_key_pressed(KeyCode::P),
         next_level: is_key_pressed(KeyCode::N),
+        hint: is_key_pressed(KeyCode::H),
+        edit: is_key_pressed(KeyCode::E),
     }
 }
 
 pub(crate) fn draw_control_panel(state: &mut AppRuntime) -> FrameActions {
     let level = state.current_level();
-    let level_code = level.code;
+    let level_code = level.code.clone();
     let level_env = level.environment;
 
     let mut actions = FrameActions::default();
-    widgets::Window::new(hash!(), vec2(18.0, 120.0), vec2(360.0, 300.0))
+    widgets::Window::new(hash!(), vec2(18.0, 120.0), vec2(360.0, 400.0))
         .label(&format!("{} Controls", level_code))
-        .ui(&mut *root_ui(), |ui| {
+        .ui(&mut root_ui(), |ui| {
             ui.label(None, &format!("Environment: {}", level_env.name));
             ui.label(
                 None,
@@ -69,6 +75,12 @@ pub(crate) fn draw_control_panel(state: &mut AppRuntime) -> FrameActions {
                 &mut state.config.height_m,
             );
             ui.slider(hash!(), "Simulation Speed", 0.5..5.0, &mut state.sim_speed);
+            if ui.button(
+                None,
+                format!("Integrator: {}", state.integrator.name()).as_str(),
+            ) {
+                state.cycle_integrator();
+            }
             ui.separator();
             if ui.button(None, "Launch (Space)") {
                 actions.launch_pause = true;
@@ -79,6 +91,17 @@ pub(crate) fn draw_control_panel(state: &mut AppRuntime) -> FrameActions {
             if ui.button(None, "Toggle Preview") {
                 state.show_preview = !state.show_preview;
             }
+            if ui.button(None, "Hint (H)") {
+                actions.hint = true;
+            }
+            let edit_label = if state.test_play.is_some() {
+                "Back to Editor (E)"
+            } else {
+                "Edit Level (E)"
+            };
+            if ui.button(None, edit_label) {
+                actions.edit = true;
+            }
             if ui.button(None, "Prev Level (P)") {
                 actions.prev_level = true;
             }
@@ -93,6 +116,10 @@ pub(crate) fn draw_control_panel(state: &mut AppRuntime) -> FrameActions {
                     state.levels_len()
                 ),
             );
+            ui.label(
+                None,
+                &format!("Hints used on this level: {}", state.current_hints_used()),
+            );
             ui.label(
                 None,
                 if state.game.paused {
@@ -0,0 +1,648 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui, widgets};
+use parabolic_rust::core::ballistics::Body;
+use parabolic_rust::core::level_file::{free_level_file_name, level_to_toml, parse_level};
+use parabolic_rust::core::sim::{
+    BounceSurface, DEFAULT_SURFACE_DAMPING, Environment, SurfaceMotion, Target, TargetMotion,
+};
+
+use crate::constants::{
+    BOTTOM_MARGIN, CONTROLS_Y, EDITOR_PANEL_HEIGHT, LEFT_MARGIN, RIGHT_MARGIN, TITLE_Y, TOP_MARGIN,
+};
+use crate::input::update_level_editor;
+use crate::model::{
+    AppScene, Barrier, EditorSelection, LaunchConfig, LaunchEditor, Level, LevelEditor,
+};
+use crate::physics::{compute_world_window, simulate_prediction, world_to_screen};
+use crate::render::{
+    draw_axis_tick_labels, draw_editor_overlay, draw_grid, draw_launch_editor, draw_level_objects,
+    draw_path, draw_ui_text,
+};
+use crate::state::AppRuntime;
+
+#[derive(Default, Clone, Copy)]
+struct EditorActions {
+    new_level: bool,
+    add_barrier: bool,
+    delete: bool,
+    fit_view: bool,
+    test_play: bool,
+    save: bool,
+    exit: bool,
+}
+
+impl EditorActions {
+    fn merge(self, other: Self) -> Self {
+        Self {
+            new_level: self.new_level || other.new_level,
+            add_barrier: self.add_barrier || other.add_barrier,
+            delete: self.delete || other.delete,
+            fit_view: self.fit_view || other.fit_view,
+            test_play: self.test_play || other.test_play,
+            save: self.save || other.save,
+            exit: self.exit || other.exit,
+        }
+    }
+}
+
+fn editor_hotkeys(mouse_screen: Vec2) -> EditorActions {
+    // Off while the mouse is over the panel, so typing a code or title is safe.
+    if root_ui().is_mouse_over(mouse_screen) {
+        return EditorActions::default();
+    }
+    EditorActions {
+        new_level: false,
+        add_barrier: is_key_pressed(KeyCode::B),
+        delete: is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace),
+        fit_view: is_key_pressed(KeyCode::F),
+        test_play: is_key_pressed(KeyCode::T),
+        save: is_key_pressed(KeyCode::S),
+        exit: is_key_pressed(KeyCode::Escape),
+    }
+}
+
+fn next_body(environment: &mut Environment) {
+    let all = Body::ALL;
+    let idx = all
+        .iter()
+        .position(|body| body.name() == environment.name)
+        .unwrap_or(0);
+    let body = all[(idx + 1) % all.len()];
+    environment.name = body.name();
+    environment.gravity_mps2 = body.gravity_mps2() as f32;
+}
+
+/// A flat pad halfway to the target, stacked above any surfaces already added.
+fn default_surface(level: &Level) -> BounceSurface {
+    let center = vec2(
+        level.target.center.x * 0.5,
+        (level.target.center.y * 0.5).max(10.0) + 15.0 * level.bounce_surfaces.len() as f32,
+    );
+    let half = vec2(30.0, 3.0);
+    BounceSurface {
+        corners: [
+            center + vec2(-half.x, half.y),
+            center + half,
+            center + vec2(half.x, -half.y),
+            center - half,
+        ],
+        restitution: 0.8,
+        friction: 0.2,
+        damping: DEFAULT_SURFACE_DAMPING,
+        motion: SurfaceMotion::Fixed,
+    }
+}
+
+/// Cycles Fixed -> Linear -> Circular -> Spline with a modest default for each.
+fn next_motion(target: &Target) -> TargetMotion {
+    match target.motion {
+        TargetMotion::Fixed => TargetMotion::Linear {
+            offset: vec2(0.0, 30.0),
+            period_s: 4.0,
+            phase: 0.0,
+        },
+        TargetMotion::Linear { .. } => TargetMotion::Circular {
+            orbit_radius_m: 15.0,
+            period_s: 5.0,
+            phase: 0.0,
+        },
+        TargetMotion::Circular { .. } => TargetMotion::Spline {
+            waypoints: vec![
+                target.center + vec2(20.0, 25.0),
+                target.center + vec2(40.0, 0.0),
+            ],
+            period_s: 6.0,
+            phase: 0.0,
+        },
+        TargetMotion::Spline { .. } => TargetMotion::Fixed,
+    }
+}
+
+fn motion_name(motion: &TargetMotion) -> &'static str {
+    match motion {
+        TargetMotion::Fixed => "Fixed",
+        TargetMotion::Linear { .. } => "Linear",
+        TargetMotion::Circular { .. } => "Circular",
+        TargetMotion::Spline { .. } => "Spline",
+    }
+}
+
+/// Cycles Fixed -> Slide -> Spin.
+fn next_surface_motion(motion: SurfaceMotion) -> SurfaceMotion {
+    match motion {
+        SurfaceMotion::Fixed => SurfaceMotion::Slide {
+            offset: vec2(0.0, 20.0),
+            period_s: 3.0,
+        },
+        SurfaceMotion::Slide { .. } => SurfaceMotion::Spin {
+            angular_velocity_radps: 90f32.to_radians(),
+        },
+        SurfaceMotion::Spin { .. } => SurfaceMotion::Fixed,
+    }
+}
+
+fn surface_motion_name(motion: SurfaceMotion) -> &'static str {
+    match motion {
+        SurfaceMotion::Fixed => "Fixed",
+        SurfaceMotion::Slide { .. } => "Slide",
+        SurfaceMotion::Spin { .. } => "Spin",
+    }
+}
+
+/// Where new obstacles appear: between the launch and the target.
+fn obstacle_spot(level: &Level) -> Vec2 {
+    vec2(
+        level.target.center.x * 0.6,
+        (level.target.center.y * 0.6).max(12.0),
+    )
+}
+
+/// An empty Earth level whose code is not yet used by the campaign.
+fn blank_level(levels: &[Level]) -> Level {
+    let code = (1..)
+        .map(|n| format!("CUSTOM {n}"))
+        .find(|code| Level::find(levels, code).is_none())
+        .expect("some custom level number is free");
+    Level {
+        code,
+        title: "Untitled".to_string(),
+        level_in_environment: 0,
+        environment: Environment {
+            name: Body::Earth.name(),
+            gravity_mps2: Body::Earth.gravity_mps2() as f32,
+            wind_accel_x_mps2: 0.0,
+            drag_linear: 0.015,
+        },
+        target: Target::fixed(vec2(150.0, 30.0), 12.0),
+        bounce_surfaces: Vec::new(),
+        barriers: Vec::new(),
+        required_bounces: 0,
+        default_launch: LaunchConfig {
+            angle_deg: 35.0,
+            speed_mps: 60.0,
+            height_m: 2.0,
+        },
+    }
+}
+
+fn delete_selection(editor: &mut LevelEditor) {
+    editor.status_line = match editor.selection.take() {
+        Some(EditorSelection::Barrier(idx)) => {
+            editor.level.barriers.remove(idx);
+            "Deleted barrier".to_string()
+        }
+        Some(EditorSelection::Surface(idx)) => {
+            editor.level.bounce_surfaces.remove(idx);
+            if editor.level.bounce_surfaces.is_empty() {
+                editor.level.required_bounces = 0;
+            }
+            "Deleted bounce surface".to_string()
+        }
+        Some(EditorSelection::Target) => {
+            editor.selection = Some(EditorSelection::Target);
+            "Every level needs its target".to_string()
+        }
+        None => "Nothing selected".to_string(),
+    };
+}
+
+fn draw_editor_panel(editor: &mut LevelEditor) -> EditorActions {
+    let mut actions = EditorActions::default();
+    widgets::Window::new(hash!(), vec2(18.0, 120.0), vec2(360.0, EDITOR_PANEL_HEIGHT))
+        .label("Level Editor")
+        .ui(&mut root_ui(), |ui| {
+            let level = &mut editor.level;
+            ui.input_text(hash!(), "Code", &mut level.code);
+            ui.input_text(hash!(), "Title", &mut level.title);
+            if ui.button(
+                None,
+                format!("Environment: {}", level.environment.name).as_str(),
+            ) {
+                next_body(&mut level.environment);
+            }
+            ui.slider(
+                hash!(),
+                "Gravity (m/s^2)",
+                0.5..30.0,
+                &mut level.environment.gravity_mps2,
+            );
+            ui.checkbox(hash!(), "Random wind each play", &mut editor.random_wind);
+            ui.slider(
+                hash!(),
+                if editor.random_wind {
+                    "Preview wind (m/s^2)"
+                } else {
+                    "Wind (m/s^2)"
+                },
+                -2.0..2.0,
+                &mut level.environment.wind_accel_x_mps2,
+            );
+            ui.slider(
+                hash!(),
+                "Drag (1/s)",
+                0.0..0.1,
+                &mut level.environment.drag_linear,
+            );
+            ui.label(
+                None,
+                &format!("Required bounces: {}", level.required_bounces),
+            );
+            if ui.button(None, "Bounces -") {
+                level.required_bounces = level.required_bounces.saturating_sub(1);
+            }
+            if ui.button(None, "Bounces +") {
+                if !level.bounce_surfaces.is_empty() {
+                    level.required_bounces += 1;
+                } else {
+                    editor.status_line = "Add a bounce surface first".to_string();
+                }
+            }
+
+            ui.separator();
+            ui.slider(
+                hash!(),
+                "Target radius (m)",
+                1.0..60.0,
+                &mut level.target.radius_m,
+            );
+            if ui.button(
+                None,
+                format!("Target motion: {}", motion_name(&level.target.motion)).as_str(),
+            ) {
+                level.target.motion = next_motion(&level.target);
+            }
+            match &mut level.target.motion {
+                TargetMotion::Fixed => {}
+                TargetMotion::Linear {
+                    offset,
+                    period_s,
+                    phase,
+                } => {
+                    ui.slider(hash!(), "Travel x (m)", -100.0..100.0, &mut offset.x);
+                    ui.slider(hash!(), "Travel y (m)", -100.0..100.0, &mut offset.y);
+                    ui.slider(hash!(), "Period (s)", 0.5..20.0, period_s);
+                    ui.slider(hash!(), "Phase", 0.0..1.0, phase);
+                }
+                TargetMotion::Circular {
+                    orbit_radius_m,
+                    period_s,
+                    phase,
+                } => {
+                    ui.slider(hash!(), "Orbit radius (m)", 1.0..100.0, orbit_radius_m);
+                    ui.slider(hash!(), "Period (s)", 0.5..20.0, period_s);
+                    ui.slider(hash!(), "Phase", 0.0..1.0, phase);
+                }
+                TargetMotion::Spline {
+                    period_s, phase, ..
+                } => {
+                    ui.slider(hash!(), "Period (s)", 0.5..20.0, period_s);
+                    ui.slider(hash!(), "Phase", 0.0..1.0, phase);
+                }
+            }
+            if let Some(EditorSelection::Barrier(idx)) = editor.selection {
+                match &mut level.barriers[idx] {
+                    Barrier::Rect(rect) => {
+                        ui.slider(hash!(), "Barrier width (m)", 0.5..200.0, &mut rect.w);
+                        ui.slider(hash!(), "Barrier height (m)", 0.5..200.0, &mut rect.h);
+                    }
+                    Barrier::Circle { radius_m, .. } => {
+                        ui.slider(hash!(), "Obstacle radius (m)", 0.5..60.0, radius_m);
+                    }
+                    Barrier::Polygon(_) => {}
+                }
+            }
+            if let Some(EditorSelection::Surface(idx)) = editor.selection {
+                let surface = &mut level.bounce_surfaces[idx];
+                ui.slider(hash!(), "Restitution", 0.0..1.0, &mut surface.restitution);
+                ui.slider(hash!(), "Friction", 0.0..1.0, &mut surface.friction);
+                ui.slider(hash!(), "Damping", 0.0..0.2, &mut surface.damping);
+                if ui.button(
+                    None,
+                    format!("Surface motion: {}", surface_motion_name(surface.motion)).as_str(),
+                ) {
+                    surface.motion = next_surface_motion(surface.motion);
+                }
+                match &mut surface.motion {
+                    SurfaceMotion::Fixed => {}
+                    SurfaceMotion::Slide { offset, period_s } => {
+                        ui.slider(hash!(), "Slide x (m)", -100.0..100.0, &mut offset.x);
+                        ui.slider(hash!(), "Slide y (m)", -100.0..100.0, &mut offset.y);
+                        ui.slider(hash!(), "Slide period (s)", 0.5..20.0, period_s);
+                    }
+                    SurfaceMotion::Spin {
+                        angular_velocity_radps,
+                    } => {
+                        let mut degps = angular_velocity_radps.to_degrees();
+                        ui.slider(hash!(), "Spin (deg/s)", -360.0..360.0, &mut degps);
+                        // Zero would be a fixed surface that the file format refuses.
+                        if degps.abs() >= 1.0 {
+                            *angular_velocity_radps = degps.to_radians();
+                        }
+                    }
+                }
+            }
+            if ui.button(None, "Add Surface") {
+                level.bounce_surfaces.push(default_surface(level));
+                let idx = level.bounce_surfaces.len() - 1;
+                editor.selection = Some(EditorSelection::Surface(idx));
+                editor.surface_editor.active_surface = idx;
+            }
+            if ui.button(None, "Draw Barrier (B)") {
+                actions.add_barrier = true;
+            }
+            if ui.button(None, "Add Circle Obstacle") {
+                level.barriers.push(Barrier::Circle {
+                    center: obstacle_spot(level),
+                    radius_m: 8.0,
+                });
+                editor.selection = Some(EditorSelection::Barrier(level.barriers.len() - 1));
+            }
+            if ui.button(None, "Add Triangle Obstacle") {
+                let spot = obstacle_spot(level);
+                level.barriers.push(Barrier::Polygon(vec![
+                    spot + vec2(-10.0, -8.0),
+                    spot + vec2(10.0, -8.0),
+                    spot + vec2(0.0, 10.0),
+                ]));
+                editor.selection = Some(EditorSelection::Barrier(level.barriers.len() - 1));
+            }
+            if ui.button(None, "Delete Selected (Del)") {
+                actions.delete = true;
+            }
+
+            ui.separator();
+            ui.slider(
+                hash!(),
+                "Launch angle (deg)",
+                -89.0..89.0,
+                &mut level.default_launch.angle_deg,
+            );
+            ui.slider(
+                hash!(),
+                "Launch velocity (m/s)",
+                5.0..500.0,
+                &mut level.default_launch.speed_mps,
+            );
+            ui.slider(
+                hash!(),
+                "Launch height (m)",
+                0.0..400.0,
+                &mut level.default_launch.height_m,
+            );
+
+            ui.separator();
+            if ui.button(None, "Test Play (T)") {
+                actions.test_play = true;
+            }
+            if ui.button(None, "Save (S)") {
+                actions.save = true;
+            }
+            if ui.button(None, "Fit View (F)") {
+                actions.fit_view = true;
+            }
+            if ui.button(None, "New Level") {
+                actions.new_level = true;
+            }
+            if ui.button(None, "Exit Editor (Esc)") {
+                actions.exit = true;
+            }
+        });
+    actions
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_level_files(file: &str, text: &str) -> Result<String, String> {
+    use parabolic_rust::core::level_file::{CAMPAIGN_MANIFEST, LEVELS_DIR, manifest_with_level};
+
+    let path = format!("{LEVELS_DIR}/{file}");
+    let manifest_path = format!("{LEVELS_DIR}/{CAMPAIGN_MANIFEST}");
+    let manifest = std::fs::read_to_string(&manifest_path)
+        .map_err(|err| format!("{manifest_path}: could not read ({err})"))?;
+    let manifest = manifest_with_level(&manifest, file).map_err(|err| err.summary())?;
+    std::fs::write(&path, text).map_err(|err| format!("{path}: could not write ({err})"))?;
+    std::fs::write(&manifest_path, manifest)
+        .map_err(|err| format!("{manifest_path}: could not write ({err})"))?;
+    Ok(path)
+}
+
+/// Code of the level saved in `assets/levels/<file>`, or `None` if there is no such
+/// file. A file that does not parse counts as some other level.
+#[cfg(not(target_arch = "wasm32"))]
+fn stored_level_code(file: &str) -> Option<String> {
+    use parabolic_rust::core::level_file::LEVELS_DIR;
+
+    let text = std::fs::read_to_string(format!("{LEVELS_DIR}/{file}")).ok()?;
+    Some(
+        parse_level(file, &text, &mut || 0.0)
+            .map(|level| level.code)
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(target_arch = "wasm32")]
+fn stored_level_code(_file: &str) -> Option<String> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_level_files(_file: &str, _text: &str) -> Result<String, String> {
+    Err("Saving level files needs the desktop build".to_string())
+}
+
+/// Writes the edited level to `assets/levels/`, lists it in the campaign manifest
+/// and adds it to the running campaign. The file is re-parsed first, so anything
+/// saved here loads again. A file already holding a different level (e.g. "EARTH-1"
+/// vs "EARTH 1", both `earth_1.toml`) is never overwritten.
+fn save_edited_level(state: &mut AppRuntime) {
+    let Some(editor) = state.editor.as_mut() else {
+        return;
+    };
+    let file = free_level_file_name(&editor.level.code, stored_level_code);
+    let text = level_to_toml(&editor.level, editor.random_wind);
+    let wind = editor.level.environment.wind_accel_x_mps2;
+    let saved = parse_level(&file, &text, &mut || wind)
+        .map_err(|err| err.summary())
+        .and_then(|_| write_level_files(&file, &text));
+    match saved {
+        Ok(path) => {
+            editor.status_line = format!("Saved {path}");
+            let level = editor.level.clone();
+            state.add_saved_level(level);
+        }
+        Err(message) => editor.status_line = format!("Not saved: {message}"),
+    }
+}
+
+pub(crate) fn run_editor_frame(
+    state: &mut AppRuntime,
+    screen_w: f32,
+    screen_h: f32,
+    font: Option<&Font>,
+) {
+    let left = LEFT_MARGIN;
+    let right = screen_w - RIGHT_MARGIN;
+    let top = TOP_MARGIN;
+    let bottom = screen_h - BOTTOM_MARGIN;
+    let mouse = mouse_position();
+    let mouse_screen = vec2(mouse.0, mouse.1);
+
+    let Some(editor) = state.editor.as_mut() else {
+        state.scene = AppScene::Game;
+        return;
+    };
+    let actions = editor_hotkeys(mouse_screen).merge(draw_editor_panel(editor));
+    if actions.exit {
+        state.close_editor();
+        return;
+    }
+    if actions.test_play {
+        state.start_test_play();
+        return;
+    }
+    if actions.new_level {
+        state.editor = Some(LevelEditor::new(blank_level(&state.levels)));
+    }
+    if actions.save {
+        save_edited_level(state);
+    }
+
+    let integrator = state.integrator;
+    let editor = state
+        .editor
+        .as_mut()
+        .expect("editor scene keeps its editor");
+    if actions.delete {
+        delete_selection(editor);
+    }
+    if actions.add_barrier {
+        editor.drawing_barrier = !editor.drawing_barrier;
+        editor.selection = None;
+        editor.status_line = if editor.drawing_barrier {
+            "Drag out the new barrier".to_string()
+        } else {
+            "Barrier drawing cancelled".to_string()
+        };
+    }
+
+    let (world_max_x, world_max_y) = editor.view;
+    update_level_editor(
+        editor,
+        mouse_screen,
+        world_max_x,
+        world_max_y,
+        left,
+        right,
+        top,
+        bottom,
+    );
+
+    let level = &editor.level;
+    let prediction = simulate_prediction(level.default_launch, level, integrator);
+    let window = compute_world_window(level, level.default_launch, &prediction, None);
+    // Only grow while editing, so nothing slides out from under the mouse.
+    editor.view = if actions.fit_view || editor.view == (1.0, 1.0) {
+        window
+    } else {
+        (editor.view.0.max(window.0), editor.view.1.max(window.1))
+    };
+    let (world_max_x, world_max_y) = editor.view;
+
+    clear_background(Color::from_rgba(250, 251, 253, 255));
+    draw_grid(
+        left,
+        right,
+        top,
+        bottom,
+        Color::from_rgba(227, 231, 236, 255),
+    );
+    draw_line(left, bottom, right, bottom, 2.0, DARKGRAY);
+    draw_line(left, top, left, bottom, 2.0, DARKGRAY);
+    draw_axis_tick_labels(left, right, top, bottom, world_max_x, world_max_y, font);
+    draw_level_objects(
+        level,
+        world_max_x,
+        world_max_y,
+        left,
+        right,
+        top,
+        bottom,
+        matches!(editor.selection, Some(EditorSelection::Surface(_))),
+        &editor.surface_editor,
+        0.0,
+        Some(prediction.flight_time_s),
+    );
+    draw_path(
+        &prediction.points,
+        world_max_x,
+        world_max_y,
+        left,
+        right,
+        top,
+        bottom,
+        2.0,
+        Color::from_rgba(76, 141, 245, 140),
+    );
+    let launch_screen = world_to_screen(
+        vec2(0.0, level.default_launch.height_m.max(0.0)),
+        world_max_x,
+        world_max_y,
+        left,
+        right,
+        top,
+        bottom,
+    );
+    draw_launch_editor(launch_screen, &LaunchEditor::new());
+    draw_editor_overlay(
+        editor,
+        mouse_screen,
+        world_max_x,
+        world_max_y,
+        left,
+        right,
+        top,
+        bottom,
+    );
+
+    let header_color = Color::from_rgba(30, 30, 35, 255);
+    draw_ui_text(
+        "ParabolicRust - Level Editor",
+        left,
+        TITLE_Y,
+        30,
+        header_color,
+        font,
+    );
+    draw_ui_text(
+        &format!(
+            "Editing: {} - {} ({}) | {} bounce(s) required",
+            level.code, level.title, level.environment.name, level.required_bounces
+        ),
+        left,
+        TITLE_Y + 30.0,
+        22,
+        DARKGRAY,
+        font,
+    );
+    draw_ui_text(
+        "Editor: click to select, drag to move | B draw barrier | Del delete | T test-play | S save | F fit view | Esc exit",
+        left + 12.0,
+        CONTROLS_Y,
+        20,
+        DARKGRAY,
+        font,
+    );
+    draw_ui_text(
+        &format!(
+            "Default launch -> {} after {:.2} s with {} bounce(s) | {}",
+            prediction.outcome.name(),
+            prediction.flight_time_s,
+            prediction.bounces,
+            editor.status_line
+        ),
+        left,
+        screen_h - 45.0,
+        20,
+        BLUE,
+        font,
+    );
+}
@@ -1,6 +1,7 @@
 use macroquad::prelude::*;
+use parabolic_rust::core::sim::{FIXED_STEP_S, MAX_SIM_TIME_S};
 
-use crate::constants::{FIXED_STEP_S, HEIGHT_KEY_RATE_MPS, MAX_SIM_TIME_S, VELOCITY_KEY_RATE_MPS};
+use crate::constants::{HEIGHT_KEY_RATE_MPS, VELOCITY_KEY_RATE_MPS};
 use crate::controls::FrameActions;
 use crate::model::{GamePhase, StepOutcome};
 use crate::physics::step_projectile;
@@ -25,6 +26,15 @@ pub(crate) fn apply_actions(state: &mut AppRuntime, actions: FrameActions) -> bo
         state.game.reset();
     }
 
+    if actions.hint && state.game.phase != GamePhase::Flying {
+        state.use_hint();
+    }
+
+    if actions.edit && state.game.phase != GamePhase::Flying {
+        state.open_editor();
+        return true;
+    }
+
     if actions.prev_level && state.current_level_idx > 0 {
         state.current_level_idx -= 1;
         state.load_current_level_defaults();
@@ -70,13 +80,14 @@ pub(crate) fn step_active_flight(state: &mut AppRuntime, frame_dt: f32) {
     let level_idx = state.current_level_idx;
     let levels_len = state.levels.len();
     let level = &state.levels[level_idx];
+    let integrator = state.integrator;
     while remaining > 0.0 {
         let dt = remaining.min(FIXED_STEP_S);
         remaining -= dt;
 
         let mut status_update: Option<(GamePhase, String)> = None;
         if let Some(shot) = state.game.shot.as_mut() {
-            let outcome = step_projectile(shot, level, dt);
+            let outcome = step_projectile(shot, level, dt, integrator);
             state.game.trail.push(shot.position);
 
             if outcome != StepOutcome::Flying {
@@ -98,9 +109,14 @@ pub(crate) fn step_active_flight(state: &mut AppRuntime, frame_dt: f32) {
                         } else {
                             " | Campaign complete"
                         };
+                        let hint_note = match state.hints_used[level_idx] {
+                            0 => String::new(),
+                            1 => " using 1 hint".to_string(),
+                            n => format!(" using {n} hints"),
+                        };
                         format!(
-                            "Target hit in {:.2}s with {} bounce(s){}",
-                            shot.elapsed_s, shot.bounces, unlock_note
+                            "Target hit in {:.2}s with {} bounce(s){}{}",
+                            shot.elapsed_s, shot.bounces, hint_note, unlock_note
                         )
                     }
                     StepOutcome::HitGround => format!(
@@ -100,7 +100,7 @@ fn draw_header_block(state: &AppRuntime, left: f32, right: f32, font: Option<&Fo
         font,
     );
     draw_ui_text(
-        "Controls: drag launch dot left/up/down for angle+speed | W/S height | A/D velocity | Space launch/pause | R reset | P/N level nav",
+        "Controls: drag launch dot left/up/down for angle+speed | W/S height | A/D velocity | Space launch/pause | R reset | H hint | P/N level nav",
         left + 12.0,
         CONTROLS_Y,
         20,
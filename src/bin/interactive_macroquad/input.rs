@@ -1,15 +1,31 @@
 use macroquad::prelude::*;
+use macroquad::ui::root_ui;
+use parabolic_rust::core::sim::{Barrier, Rect as WorldRect};
 
 use crate::constants::{
-    LAUNCH_DRAG_MIN_PX, LAUNCH_GHOST_BELOW_AXIS_PX, LAUNCH_HANDLE_RADIUS, ROTATE_HANDLE_RADIUS,
-    SLINGSHOT_VERTICAL_MIRROR, SURFACE_HANDLE_RADIUS,
+    LAUNCH_DRAG_MIN_PX, LAUNCH_GHOST_BELOW_AXIS_PX, LAUNCH_HANDLE_RADIUS, MIN_BARRIER_SIZE_M,
+    ROTATE_HANDLE_RADIUS, SLINGSHOT_VERTICAL_MIRROR, SURFACE_HANDLE_RADIUS,
+};
+use crate::model::{
+    EditorDrag, EditorSelection, GamePhase, LaunchConfig, LaunchEditor, Level, LevelEditor,
+    SurfaceDragMode, SurfaceEditor,
 };
-use crate::model::{GamePhase, LaunchConfig, LaunchEditor, Level, SurfaceDragMode, SurfaceEditor};
 use crate::physics::{
     point_in_polygon, quad_center, rotate_vec, rotation_handle_screen, screen_to_world,
     world_to_screen,
 };
 
+/// Which of a surface's handles the mouse is over: corner, rotation handle, inside.
+fn surface_hover(corners_screen: &[Vec2; 4], mouse_screen: Vec2) -> (Option<usize>, bool, bool) {
+    let corner = corners_screen
+        .iter()
+        .position(|corner| mouse_screen.distance(*corner) <= (SURFACE_HANDLE_RADIUS + 4.0));
+    let (_rotate_anchor, rotate_handle) = rotation_handle_screen(corners_screen);
+    let rotate = mouse_screen.distance(rotate_handle) <= (ROTATE_HANDLE_RADIUS + 4.0);
+    let inside = point_in_polygon(mouse_screen, corners_screen);
+    (corner, rotate, inside)
+}
+
 pub(crate) fn update_surface_editor(
     level: &mut Level,
     editor: &mut SurfaceEditor,
@@ -21,33 +37,48 @@ pub(crate) fn update_surface_editor(
     top: f32,
     bottom: f32,
 ) -> bool {
-    let Some(surface) = level.bounce_surface.as_mut() else {
+    if level.bounce_surfaces.is_empty() {
         editor.drag_mode = None;
         editor.hovered_corner = None;
         editor.hovered_surface = false;
         editor.hovered_rotate = false;
         return false;
-    };
+    }
 
     if !is_mouse_button_down(MouseButton::Left) {
         editor.drag_mode = None;
     }
 
-    let corners_screen = surface
-        .corners
-        .map(|corner| world_to_screen(corner, world_max_x, world_max_y, left, right, top, bottom));
-
-    let mut hovered_corner = None;
-    for (idx, corner) in corners_screen.iter().copied().enumerate() {
-        if mouse_screen.distance(corner) <= (SURFACE_HANDLE_RADIUS + 4.0) {
-            hovered_corner = Some(idx);
-            break;
+    let surfaces_screen: Vec<[Vec2; 4]> = level
+        .bounce_surfaces
+        .iter()
+        .map(|surface| {
+            surface.corners.map(|corner| {
+                world_to_screen(corner, world_max_x, world_max_y, left, right, top, bottom)
+            })
+        })
+        .collect();
+    editor.active_surface = editor.active_surface.min(surfaces_screen.len() - 1);
+    if !editor.is_dragging() {
+        // The live surface keeps its handles while hovered; otherwise the topmost
+        // surface under the mouse takes over.
+        let (corner, rotate, inside) =
+            surface_hover(&surfaces_screen[editor.active_surface], mouse_screen);
+        if corner.is_none()
+            && !rotate
+            && !inside
+            && let Some(idx) = surfaces_screen
+                .iter()
+                .rposition(|corners| point_in_polygon(mouse_screen, corners))
+        {
+            editor.active_surface = idx;
         }
     }
+    let corners_screen = surfaces_screen[editor.active_surface];
+    let surface = &mut level.bounce_surfaces[editor.active_surface];
 
-    let (_rotate_anchor, rotate_handle) = rotation_handle_screen(&corners_screen);
-    let hovered_rotate = mouse_screen.distance(rotate_handle) <= (ROTATE_HANDLE_RADIUS + 4.0);
-    let hovered_inside = point_in_polygon(mouse_screen, &corners_screen);
+    let (hovered_corner, hovered_rotate, hovered_inside) =
+        surface_hover(&corners_screen, mouse_screen);
     let hovered_surface = hovered_corner.is_some() || hovered_inside || hovered_rotate;
 
     editor.hovered_corner = hovered_corner;
@@ -256,3 +287,190 @@ pub(crate) fn update_launch_editor(
     config.angle_deg = angle;
     true
 }
+
+/// World point an editor selection is dragged by.
+fn selection_origin(level: &Level, selection: EditorSelection) -> Vec2 {
+    match selection {
+        EditorSelection::Target => level.target.position_at(0.0),
+        EditorSelection::Barrier(idx) => {
+            let bounds = level.barriers[idx].bounds();
+            vec2(bounds.x, bounds.y)
+        }
+        EditorSelection::Surface(idx) => level.bounce_surfaces[idx].corners[0],
+    }
+}
+
+fn move_selection(level: &mut Level, selection: EditorSelection, origin: Vec2) {
+    match selection {
+        EditorSelection::Target => {
+            let target = &mut level.target;
+            target.translate(origin.max(Vec2::ZERO) - target.position_at(0.0));
+        }
+        EditorSelection::Barrier(idx) => {
+            let barrier = &mut level.barriers[idx];
+            let bounds = barrier.bounds();
+            barrier.translate(origin.max(Vec2::ZERO) - vec2(bounds.x, bounds.y));
+        }
+        EditorSelection::Surface(idx) => {
+            let surface = &mut level.bounce_surfaces[idx];
+            let min = surface.corners.iter().fold(Vec2::MAX, |acc, c| acc.min(*c));
+            // Keep the whole surface in positive world, like the corner drags.
+            let delta = (origin - surface.corners[0]).max(-min);
+            surface.corners = surface.corners.map(|corner| corner + delta);
+        }
+    }
+}
+
+/// Topmost editable object under the mouse: target, then barriers (last drawn
+/// first), then the bounce surface.
+fn pick_selection(
+    level: &Level,
+    mouse_screen: Vec2,
+    mouse_world: Vec2,
+    world_max_x: f32,
+    world_max_y: f32,
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+) -> Option<EditorSelection> {
+    let target_screen = world_to_screen(
+        level.target.position_at(0.0),
+        world_max_x,
+        world_max_y,
+        left,
+        right,
+        top,
+        bottom,
+    );
+    let px_per_world =
+        ((right - left) / world_max_x.max(1.0)).min((bottom - top) / world_max_y.max(1.0));
+    let target_radius_px = (level.target.radius_m * px_per_world).max(SURFACE_HANDLE_RADIUS);
+    if mouse_screen.distance(target_screen) <= target_radius_px {
+        return Some(EditorSelection::Target);
+    }
+    if let Some(idx) = level
+        .barriers
+        .iter()
+        .rposition(|barrier| barrier.contains(mouse_world))
+    {
+        return Some(EditorSelection::Barrier(idx));
+    }
+    level
+        .bounce_surfaces
+        .iter()
+        .rposition(|surface| point_in_polygon(mouse_world, &surface.corners))
+        .map(EditorSelection::Surface)
+}
+
+/// Editor mouse tools: select and drag objects, reshape the selected bounce surface
+/// with its handles, and rubber-band new barriers. Returns true if the level changed.
+pub(crate) fn update_level_editor(
+    editor: &mut LevelEditor,
+    mouse_screen: Vec2,
+    world_max_x: f32,
+    world_max_y: f32,
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+) -> bool {
+    let mouse_world = screen_to_world(
+        mouse_screen,
+        world_max_x,
+        world_max_y,
+        left,
+        right,
+        top,
+        bottom,
+    )
+    .min(vec2(world_max_x, world_max_y));
+
+    if !is_mouse_button_down(MouseButton::Left) {
+        if let Some(EditorDrag::NewBarrier { start }) = editor.drag {
+            let min = start.min(mouse_world);
+            let size = (start - mouse_world).abs();
+            if size.x >= MIN_BARRIER_SIZE_M && size.y >= MIN_BARRIER_SIZE_M {
+                editor
+                    .level
+                    .barriers
+                    .push(Barrier::Rect(WorldRect::new(min.x, min.y, size.x, size.y)));
+                editor.selection = Some(EditorSelection::Barrier(editor.level.barriers.len() - 1));
+                editor.drawing_barrier = false;
+                editor.status_line = format!("Added barrier {:.1} x {:.1} m", size.x, size.y);
+            }
+        }
+        editor.drag = None;
+    }
+
+    let over_panel = editor.drag.is_none()
+        && !editor.surface_editor.is_dragging()
+        && root_ui().is_mouse_over(mouse_screen);
+    if over_panel {
+        return false;
+    }
+
+    if matches!(editor.selection, Some(EditorSelection::Surface(_))) && editor.drag.is_none() {
+        let on_handles = update_surface_editor(
+            &mut editor.level,
+            &mut editor.surface_editor,
+            mouse_screen,
+            world_max_x,
+            world_max_y,
+            left,
+            right,
+            top,
+            bottom,
+        );
+        editor.selection = Some(EditorSelection::Surface(
+            editor.surface_editor.active_surface,
+        ));
+        if editor.surface_editor.is_dragging() {
+            return true;
+        }
+        if on_handles && is_mouse_button_pressed(MouseButton::Left) {
+            return false;
+        }
+    }
+
+    if is_mouse_button_pressed(MouseButton::Left) {
+        let picked = if editor.drawing_barrier {
+            None
+        } else {
+            pick_selection(
+                &editor.level,
+                mouse_screen,
+                mouse_world,
+                world_max_x,
+                world_max_y,
+                left,
+                right,
+                top,
+                bottom,
+            )
+        };
+        editor.selection = picked;
+        if let Some(EditorSelection::Surface(idx)) = picked {
+            editor.surface_editor.active_surface = idx;
+        }
+        editor.drag = match picked {
+            Some(selection) => Some(EditorDrag::Move {
+                selection,
+                grab_offset: selection_origin(&editor.level, selection) - mouse_world,
+            }),
+            None if editor.drawing_barrier => Some(EditorDrag::NewBarrier { start: mouse_world }),
+            None => None,
+        };
+    }
+
+    match editor.drag {
+        Some(EditorDrag::Move {
+            selection,
+            grab_offset,
+        }) => {
+            move_selection(&mut editor.level, selection, mouse_world + grab_offset);
+            true
+        }
+        _ => false,
+    }
+}
@@ -1,8 +1,11 @@
+#![allow(clippy::too_many_arguments)]
+
 use macroquad::prelude::Conf;
 
 mod app;
 mod constants;
 mod controls;
+mod editor;
 mod gameplay;
 mod hud;
 mod input;
@@ -1,326 +1,72 @@
 use macroquad::prelude::*;
 use macroquad::rand::gen_range;
-
-use crate::physics::launch_projectile;
-
-#[derive(Clone, Copy)]
-pub(crate) struct LaunchConfig {
-    pub(crate) angle_deg: f32,
-    pub(crate) speed_mps: f32,
-    pub(crate) height_m: f32,
-}
-
-#[derive(Clone, Copy)]
-pub(crate) struct Environment {
-    pub(crate) name: &'static str,
-    pub(crate) gravity_mps2: f32,
-    pub(crate) wind_accel_x_mps2: f32,
-    pub(crate) drag_linear: f32,
-}
-
-#[derive(Clone, Copy)]
-pub(crate) struct BounceSurface {
-    pub(crate) corners: [Vec2; 4],
-    pub(crate) restitution: f32,
-}
-
-#[derive(Clone, Copy)]
-pub(crate) struct Target {
-    pub(crate) center: Vec2,
-    pub(crate) radius_m: f32,
-}
-
-#[derive(Clone, Copy)]
-pub(crate) struct Barrier {
-    pub(crate) rect: Rect,
-}
-
-pub(crate) struct Level {
-    pub(crate) code: &'static str,
-    pub(crate) title: &'static str,
-    pub(crate) level_in_environment: usize,
-    pub(crate) environment: Environment,
-    pub(crate) target: Target,
-    pub(crate) bounce_surface: Option<BounceSurface>,
-    pub(crate) barriers: Vec<Barrier>,
-    pub(crate) required_bounces: u32,
-    pub(crate) default_launch: LaunchConfig,
-}
-
-impl Level {
-    pub(crate) fn campaign() -> Vec<Self> {
-        let mut levels = Self::earth_campaign();
-        levels.extend(Self::moon_campaign());
-        levels
-    }
-
-    fn random_earth_wind_mps2() -> f32 {
-        let magnitude = gen_range(0.20f32, 0.80f32);
-        let sign = if gen_range(0i32, 2i32) == 0 {
-            -1.0
-        } else {
-            1.0
-        };
-        sign * magnitude
-    }
-
-    pub(crate) fn earth_campaign() -> Vec<Self> {
-        let earth_drag = 0.015;
-        vec![
-            Self {
-                code: "EARTH 1",
-                title: "Direct Shot",
-                level_in_environment: 1,
-                environment: Environment {
-                    name: "Earth",
-                    gravity_mps2: 9.8,
-                    wind_accel_x_mps2: Self::random_earth_wind_mps2(),
-                    drag_linear: earth_drag,
-                },
-                target: Target {
-                    center: vec2(165.0, 28.0),
-                    radius_m: 12.0,
-                },
-                bounce_surface: None,
-                barriers: vec![],
-                required_bounces: 0,
-                default_launch: LaunchConfig {
-                    angle_deg: 34.0,
-                    speed_mps: 56.0,
-                    height_m: 2.0,
-                },
-            },
-            Self {
-                code: "EARTH 2",
-                title: "Single Bounce",
-                level_in_environment: 2,
-                environment: Environment {
-                    name: "Earth",
-                    gravity_mps2: 9.8,
-                    wind_accel_x_mps2: Self::random_earth_wind_mps2(),
-                    drag_linear: earth_drag,
-                },
-                target: Target {
-                    center: vec2(208.0, 36.0),
-                    radius_m: 12.0,
-                },
-                bounce_surface: Some(BounceSurface {
-                    corners: [
-                        vec2(86.0, 19.0),
-                        vec2(146.0, 19.0),
-                        vec2(146.0, 13.0),
-                        vec2(86.0, 13.0),
-                    ],
-                    restitution: 0.82,
-                }),
-                barriers: vec![],
-                required_bounces: 1,
-                default_launch: LaunchConfig {
-                    angle_deg: 31.0,
-                    speed_mps: 58.0,
-                    height_m: 2.0,
-                },
-            },
-            Self {
-                code: "EARTH 3",
-                title: "Thread The Gap",
-                level_in_environment: 3,
-                environment: Environment {
-                    name: "Earth",
-                    gravity_mps2: 9.8,
-                    wind_accel_x_mps2: Self::random_earth_wind_mps2(),
-                    drag_linear: earth_drag,
-                },
-                target: Target {
-                    center: vec2(230.0, 34.0),
-                    radius_m: 12.0,
-                },
-                bounce_surface: None,
-                barriers: vec![
-                    Barrier {
-                        rect: Rect::new(133.0, 0.0, 9.0, 24.0),
-                    },
-                    Barrier {
-                        rect: Rect::new(133.0, 58.0, 9.0, 42.0),
-                    },
-                ],
-                required_bounces: 0,
-                default_launch: LaunchConfig {
-                    angle_deg: 30.0,
-                    speed_mps: 64.0,
-                    height_m: 2.5,
-                },
-            },
-            Self {
-                code: "EARTH 4",
-                title: "Bank Shot Through Gap",
-                level_in_environment: 4,
-                environment: Environment {
-                    name: "Earth",
-                    gravity_mps2: 9.8,
-                    wind_accel_x_mps2: Self::random_earth_wind_mps2(),
-                    drag_linear: earth_drag,
-                },
-                target: Target {
-                    center: vec2(280.0, 36.0),
-                    radius_m: 12.0,
-                },
-                bounce_surface: Some(BounceSurface {
-                    corners: [
-                        vec2(120.0, 22.0),
-                        vec2(188.0, 22.0),
-                        vec2(188.0, 15.0),
-                        vec2(120.0, 15.0),
-                    ],
-                    restitution: 0.80,
-                }),
-                barriers: vec![
-                    Barrier {
-                        rect: Rect::new(208.0, 0.0, 10.0, 28.0),
-                    },
-                    Barrier {
-                        rect: Rect::new(208.0, 62.0, 10.0, 38.0),
-                    },
-                ],
-                required_bounces: 1,
-                default_launch: LaunchConfig {
-                    angle_deg: 33.0,
-                    speed_mps: 67.0,
-                    height_m: 3.0,
-                },
-            },
-        ]
+use parabolic_rust::core::campaign::EARTH_WIND_RANGE_MPS2;
+use parabolic_rust::core::level_file::{
+    CAMPAIGN_MANIFEST, LEVELS_DIR, LevelFileError, parse_campaign, parse_manifest,
+};
+use parabolic_rust::core::sim::launch_projectile;
+
+pub(crate) use parabolic_rust::core::sim::{
+    Barrier, LaunchConfig, Level, Prediction, Projectile, StepOutcome, SurfaceMotion,
+};
+
+/// Loads the campaign from `assets/levels/`. If any file is missing or invalid, the
+/// built-in levels are used instead and every problem is returned for display.
+pub(crate) async fn load_campaign() -> (Vec<Level>, Vec<String>) {
+    let errors = match read_campaign_files().await {
+        Ok(files) => match parse_campaign(&files, random_earth_wind_mps2) {
+            Ok(levels) => return (levels, Vec::new()),
+            Err(errors) => {
+                // The console gets the full TOML snippet; the title screen, one line each.
+                for error in &errors {
+                    eprintln!("Level load error: {error}");
+                }
+                errors.iter().map(LevelFileError::summary).collect()
+            }
+        },
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("Level load error: {error}");
+            }
+            errors
+        }
+    };
+    (Level::campaign(random_earth_wind_mps2), errors)
+}
+
+/// Reads the manifest and every level file it lists (works on web, where the
+/// directory itself cannot be listed).
+async fn read_campaign_files() -> Result<Vec<(String, String)>, Vec<String>> {
+    let manifest_path = format!("{LEVELS_DIR}/{CAMPAIGN_MANIFEST}");
+    let manifest = load_string(&manifest_path)
+        .await
+        .map_err(|err| vec![format!("{manifest_path}: could not read ({err})")])?;
+    let names = parse_manifest(&manifest).map_err(|err| vec![err.summary()])?;
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for name in names {
+        match load_string(&format!("{LEVELS_DIR}/{name}")).await {
+            Ok(text) => files.push((name, text)),
+            Err(err) => errors.push(format!("{name}: could not read ({err})")),
+        }
     }
-
-    pub(crate) fn moon_campaign() -> Vec<Self> {
-        let moon_env = Environment {
-            name: "Moon",
-            gravity_mps2: 1.62,
-            wind_accel_x_mps2: 0.0,
-            drag_linear: 0.0,
-        };
-
-        vec![
-            Self {
-                code: "MOON 1",
-                title: "Direct Shot",
-                level_in_environment: 1,
-                environment: moon_env,
-                target: Target {
-                    center: vec2(700.0, 130.0),
-                    radius_m: 30.0,
-                },
-                bounce_surface: None,
-                barriers: vec![],
-                required_bounces: 0,
-                default_launch: LaunchConfig {
-                    angle_deg: 18.0,
-                    speed_mps: 90.0,
-                    height_m: 20.0,
-                },
-            },
-            Self {
-                code: "MOON 2",
-                title: "Bounce Into Target",
-                level_in_environment: 2,
-                environment: moon_env,
-                target: Target {
-                    center: vec2(980.0, 190.0),
-                    radius_m: 35.0,
-                },
-                bounce_surface: Some(BounceSurface {
-                    corners: [
-                        vec2(380.0, 95.0),
-                        vec2(690.0, 95.0),
-                        vec2(690.0, 75.0),
-                        vec2(380.0, 75.0),
-                    ],
-                    restitution: 0.9,
-                }),
-                barriers: vec![],
-                required_bounces: 1,
-                default_launch: LaunchConfig {
-                    angle_deg: 28.0,
-                    speed_mps: 145.0,
-                    height_m: 22.0,
-                },
-            },
-            Self {
-                code: "MOON 3",
-                title: "Thread The Gap",
-                level_in_environment: 3,
-                environment: moon_env,
-                target: Target {
-                    center: vec2(980.0, 190.0),
-                    radius_m: 32.0,
-                },
-                bounce_surface: None,
-                barriers: vec![
-                    Barrier {
-                        rect: Rect::new(560.0, 0.0, 38.0, 230.0),
-                    },
-                    Barrier {
-                        rect: Rect::new(560.0, 310.0, 38.0, 260.0),
-                    },
-                ],
-                required_bounces: 0,
-                default_launch: LaunchConfig {
-                    angle_deg: 20.0,
-                    speed_mps: 145.0,
-                    height_m: 24.0,
-                },
-            },
-            Self {
-                code: "MOON 4",
-                title: "Bank Shot Through Gap",
-                level_in_environment: 4,
-                environment: moon_env,
-                target: Target {
-                    center: vec2(1110.0, 220.0),
-                    radius_m: 32.0,
-                },
-                bounce_surface: Some(BounceSurface {
-                    corners: [
-                        vec2(420.0, 106.0),
-                        vec2(710.0, 106.0),
-                        vec2(710.0, 84.0),
-                        vec2(420.0, 84.0),
-                    ],
-                    restitution: 0.88,
-                }),
-                barriers: vec![
-                    Barrier {
-                        rect: Rect::new(790.0, 0.0, 36.0, 250.0),
-                    },
-                    Barrier {
-                        rect: Rect::new(790.0, 340.0, 36.0, 260.0),
-                    },
-                ],
-                required_bounces: 1,
-                default_launch: LaunchConfig {
-                    angle_deg: 24.0,
-                    speed_mps: 170.0,
-                    height_m: 30.0,
-                },
-            },
-        ]
+    if errors.is_empty() {
+        Ok(files)
+    } else {
+        Err(errors)
     }
 }
 
-#[derive(Clone, Copy)]
-pub(crate) struct Projectile {
-    pub(crate) position: Vec2,
-    pub(crate) velocity: Vec2,
-    pub(crate) elapsed_s: f32,
-    pub(crate) bounces: u32,
-}
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub(crate) enum StepOutcome {
-    Flying,
-    HitTarget,
-    HitGround,
-    HitBarrier,
+fn random_earth_wind_mps2() -> f32 {
+    let (min_mps2, max_mps2) = EARTH_WIND_RANGE_MPS2;
+    let magnitude = gen_range(min_mps2, max_mps2);
+    let sign = if gen_range(0i32, 2i32) == 0 {
+        -1.0
+    } else {
+        1.0
+    };
+    sign * magnitude
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -335,6 +81,7 @@ pub(crate) enum GamePhase {
 pub(crate) enum AppScene {
     Title,
     Game,
+    Editor,
 }
 
 pub(crate) struct GameState {
@@ -382,6 +129,8 @@ pub(crate) enum SurfaceDragMode {
 }
 
 pub(crate) struct SurfaceEditor {
+    /// Index into `Level::bounce_surfaces` of the surface whose handles are live.
+    pub(crate) active_surface: usize,
     pub(crate) drag_mode: Option<SurfaceDragMode>,
     pub(crate) hovered_corner: Option<usize>,
     pub(crate) hovered_surface: bool,
@@ -395,6 +144,7 @@ pub(crate) struct SurfaceEditor {
 impl SurfaceEditor {
     pub(crate) fn new() -> Self {
         Self {
+            active_surface: 0,
             drag_mode: None,
             hovered_corner: None,
             hovered_surface: false,
@@ -438,10 +188,52 @@ impl LaunchEditor {
     }
 }
 
-pub(crate) struct Prediction {
-    pub(crate) points: Vec<Vec2>,
-    pub(crate) range_m: f32,
-    pub(crate) flight_time_s: f32,
-    pub(crate) bounces: u32,
-    pub(crate) outcome: StepOutcome,
+/// What the level editor's mouse tools act on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum EditorSelection {
+    Target,
+    Barrier(usize),
+    Surface(usize),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum EditorDrag {
+    /// Moving an existing object; `grab_offset` is the object origin minus the mouse.
+    Move {
+        selection: EditorSelection,
+        grab_offset: Vec2,
+    },
+    /// Rubber-banding a new barrier from `start`.
+    NewBarrier { start: Vec2 },
+}
+
+pub(crate) struct LevelEditor {
+    pub(crate) level: Level,
+    /// Save the wind as `"random"` (the Earth roll) rather than the preview value.
+    pub(crate) random_wind: bool,
+    pub(crate) selection: Option<EditorSelection>,
+    pub(crate) drag: Option<EditorDrag>,
+    /// Next drag on empty space draws a barrier instead of deselecting.
+    pub(crate) drawing_barrier: bool,
+    pub(crate) surface_editor: SurfaceEditor,
+    /// World window; only grows while editing so objects do not jump under the mouse.
+    pub(crate) view: (f32, f32),
+    pub(crate) status_line: String,
+}
+
+impl LevelEditor {
+    pub(crate) fn new(level: Level) -> Self {
+        let random_wind =
+            level.environment.name == "Earth" && level.environment.wind_accel_x_mps2 != 0.0;
+        Self {
+            status_line: format!("Editing {}", level.code),
+            level,
+            random_wind,
+            selection: None,
+            drag: None,
+            drawing_barrier: false,
+            surface_editor: SurfaceEditor::new(),
+            view: (1.0, 1.0),
+        }
+    }
 }
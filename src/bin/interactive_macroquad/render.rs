@@ -1,10 +1,14 @@
 use macroquad::prelude::*;
 
 use crate::constants::{
-    LAUNCH_GHOST_RADIUS, LAUNCH_HANDLE_RADIUS, ROTATE_HANDLE_RADIUS, START_BUTTON_COLOR,
-    START_BUTTON_TEXT, SURFACE_HANDLE_RADIUS, TITLE_SCREEN_BG, X_GRID_LINES, Y_GRID_LINES,
+    LAUNCH_GHOST_RADIUS, LAUNCH_HANDLE_RADIUS, MAX_TITLE_ERRORS, ROTATE_HANDLE_RADIUS,
+    START_BUTTON_COLOR, START_BUTTON_TEXT, SURFACE_HANDLE_RADIUS, TITLE_SCREEN_BG, X_GRID_LINES,
+    Y_GRID_LINES,
+};
+use crate::model::{
+    AppScene, Barrier, EditorDrag, EditorSelection, LaunchEditor, Level, LevelEditor,
+    SurfaceEditor, SurfaceMotion,
 };
-use crate::model::{LaunchEditor, Level, SurfaceEditor};
 use crate::physics::{bounce_surface_edges, rotation_handle_screen, world_to_screen};
 
 fn format_axis_value(value: f32, axis_max: f32) -> String {
@@ -116,11 +120,36 @@ pub(crate) fn draw_level_objects(
     bottom: f32,
     show_surface_handles: bool,
     editor: &SurfaceEditor,
+    time_s: f32,
+    preview_time_s: Option<f32>,
 ) {
-    if let Some(surface) = level.bounce_surface {
-        let corners = surface.corners.map(|corner| {
-            world_to_screen(corner, world_max_x, world_max_y, left, right, top, bottom)
-        });
+    let to_screen =
+        |world: Vec2| world_to_screen(world, world_max_x, world_max_y, left, right, top, bottom);
+    for (surface_idx, surface) in level.bounce_surfaces.iter().enumerate() {
+        let sweep_color = Color::from_rgba(242, 159, 5, 90);
+        match surface.motion {
+            SurfaceMotion::Fixed => {}
+            SurfaceMotion::Slide { period_s, .. } => {
+                // Outline the far end of the slide.
+                let far = surface.corners_at(period_s * 0.5).map(to_screen);
+                for (a, b) in bounce_surface_edges(&far) {
+                    draw_line(a.x, a.y, b.x, b.y, 1.5, sweep_color);
+                }
+            }
+            SurfaceMotion::Spin { .. } => {
+                let pivot = surface.pivot();
+                let reach = surface
+                    .corners
+                    .iter()
+                    .fold(0.0f32, |acc, corner| acc.max(corner.distance(pivot)));
+                let center = to_screen(pivot);
+                let px_per_world = ((right - left) / world_max_x.max(1.0))
+                    .min((bottom - top) / world_max_y.max(1.0));
+                draw_circle_lines(center.x, center.y, reach * px_per_world, 1.5, sweep_color);
+            }
+        }
+
+        let corners = surface.corners_at(time_s).map(to_screen);
         draw_triangle(
             corners[0],
             corners[1],
@@ -138,7 +167,10 @@ pub(crate) fn draw_level_objects(
             draw_line(a.x, a.y, b.x, b.y, 3.0, Color::from_rgba(242, 159, 5, 255));
         }
 
-        if show_surface_handles || editor.is_dragging() {
+        // Handles edit the rest pose, so a moving surface only shows them at rest.
+        let at_rest = time_s == 0.0 || surface.motion == SurfaceMotion::Fixed;
+        let is_live = surface_idx == editor.active_surface && at_rest;
+        if is_live && (show_surface_handles || editor.is_dragging()) {
             let active_corner = editor.active_corner();
             for (idx, corner) in corners.iter().copied().enumerate() {
                 let is_active = active_corner == Some(idx);
@@ -195,18 +227,29 @@ pub(crate) fn draw_level_objects(
         }
     }
 
-    let target_center = world_to_screen(
-        level.target.center,
-        world_max_x,
-        world_max_y,
-        left,
-        right,
-        top,
-        bottom,
-    );
     let px_per_world_x = (right - left) / world_max_x.max(1.0);
     let px_per_world_y = (bottom - top) / world_max_y.max(1.0);
     let target_radius_px = (level.target.radius_m * px_per_world_x.min(px_per_world_y)).max(4.0);
+    if level.target.period_s().is_some() {
+        let path: Vec<Vec2> = level.target.motion_path(64);
+        for pair in path.windows(2) {
+            let (a, b) = (to_screen(pair[0]), to_screen(pair[1]));
+            draw_line(a.x, a.y, b.x, b.y, 1.5, Color::from_rgba(81, 201, 122, 140));
+        }
+        // Where the target will be when the previewed shot arrives.
+        if let Some(time_s) = preview_time_s {
+            let ghost = to_screen(level.target.position_at(time_s));
+            draw_circle_lines(
+                ghost.x,
+                ghost.y,
+                target_radius_px,
+                2.0,
+                Color::from_rgba(81, 201, 122, 200),
+            );
+        }
+    }
+
+    let target_center = to_screen(level.target.position_at(time_s));
     draw_circle(
         target_center.x,
         target_center.y,
@@ -221,34 +264,60 @@ pub(crate) fn draw_level_objects(
         DARKGREEN,
     );
 
+    let barrier_color = Color::from_rgba(170, 84, 84, 220);
+    let px_per_world = px_per_world_x.min(px_per_world_y);
     for barrier in &level.barriers {
-        let top_left_world = vec2(barrier.rect.x, barrier.rect.y + barrier.rect.h);
-        let bottom_right_world = vec2(barrier.rect.x + barrier.rect.w, barrier.rect.y);
-        let top_left = world_to_screen(
-            top_left_world,
-            world_max_x,
-            world_max_y,
-            left,
-            right,
-            top,
-            bottom,
-        );
-        let bottom_right = world_to_screen(
-            bottom_right_world,
-            world_max_x,
-            world_max_y,
-            left,
-            right,
-            top,
-            bottom,
-        );
-        draw_rectangle(
-            top_left.x,
-            top_left.y,
-            (bottom_right.x - top_left.x).max(2.0),
-            (bottom_right.y - top_left.y).max(2.0),
-            Color::from_rgba(170, 84, 84, 220),
-        );
+        match barrier {
+            Barrier::Rect(rect) => {
+                let top_left = world_to_screen(
+                    vec2(rect.x, rect.y + rect.h),
+                    world_max_x,
+                    world_max_y,
+                    left,
+                    right,
+                    top,
+                    bottom,
+                );
+                let bottom_right = world_to_screen(
+                    vec2(rect.x + rect.w, rect.y),
+                    world_max_x,
+                    world_max_y,
+                    left,
+                    right,
+                    top,
+                    bottom,
+                );
+                draw_rectangle(
+                    top_left.x,
+                    top_left.y,
+                    (bottom_right.x - top_left.x).max(2.0),
+                    (bottom_right.y - top_left.y).max(2.0),
+                    barrier_color,
+                );
+            }
+            Barrier::Polygon(points) => {
+                let screen: Vec<Vec2> = points
+                    .iter()
+                    .map(|p| {
+                        world_to_screen(*p, world_max_x, world_max_y, left, right, top, bottom)
+                    })
+                    .collect();
+                // Convex, so a fan from the first corner covers it.
+                for pair in screen[1..].windows(2) {
+                    draw_triangle(screen[0], pair[0], pair[1], barrier_color);
+                }
+            }
+            Barrier::Circle { center, radius_m } => {
+                let center =
+                    world_to_screen(*center, world_max_x, world_max_y, left, right, top, bottom);
+                draw_circle(
+                    center.x,
+                    center.y,
+                    (radius_m * px_per_world).max(2.0),
+                    barrier_color,
+                );
+            }
+        }
     }
 }
 
@@ -329,7 +398,79 @@ pub(crate) fn draw_launch_editor(launch_screen: Vec2, editor: &LaunchEditor) {
     }
 }
 
-pub(crate) fn draw_title_screen(screen_w: f32, screen_h: f32, font: Option<&Font>) -> bool {
+/// Selection outline and the barrier being rubber-banded in the level editor.
+pub(crate) fn draw_editor_overlay(
+    editor: &LevelEditor,
+    mouse_screen: Vec2,
+    world_max_x: f32,
+    world_max_y: f32,
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+) {
+    let highlight = Color::from_rgba(37, 99, 235, 255);
+    let to_screen =
+        |world: Vec2| world_to_screen(world, world_max_x, world_max_y, left, right, top, bottom);
+    let draw_world_rect = |min: Vec2, max: Vec2, color: Color| {
+        let top_left = to_screen(vec2(min.x, max.y));
+        let bottom_right = to_screen(vec2(max.x, min.y));
+        draw_rectangle_lines(
+            top_left.x - 3.0,
+            top_left.y - 3.0,
+            (bottom_right.x - top_left.x) + 6.0,
+            (bottom_right.y - top_left.y) + 6.0,
+            2.0,
+            color,
+        );
+    };
+
+    match editor.selection {
+        Some(EditorSelection::Target) => {
+            let center = to_screen(editor.level.target.position_at(0.0));
+            let px_per_world =
+                ((right - left) / world_max_x.max(1.0)).min((bottom - top) / world_max_y.max(1.0));
+            let radius_px = (editor.level.target.radius_m * px_per_world).max(4.0);
+            draw_circle_lines(center.x, center.y, radius_px + 4.0, 2.0, highlight);
+        }
+        Some(EditorSelection::Barrier(idx)) => {
+            let rect = editor.level.barriers[idx].bounds();
+            draw_world_rect(
+                vec2(rect.x, rect.y),
+                vec2(rect.x + rect.w, rect.y + rect.h),
+                highlight,
+            );
+        }
+        Some(EditorSelection::Surface(idx)) => {
+            let corners = editor.level.bounce_surfaces[idx].corners.map(to_screen);
+            for (a, b) in bounce_surface_edges(&corners) {
+                draw_line(a.x, a.y, b.x, b.y, 1.5, highlight);
+            }
+        }
+        None => {}
+    }
+
+    if let Some(EditorDrag::NewBarrier { start }) = editor.drag {
+        let start_screen = to_screen(start);
+        let min = start_screen.min(mouse_screen);
+        let size = (start_screen - mouse_screen).abs();
+        draw_rectangle(
+            min.x,
+            min.y,
+            size.x,
+            size.y,
+            Color::from_rgba(170, 84, 84, 110),
+        );
+        draw_rectangle_lines(min.x, min.y, size.x, size.y, 2.0, highlight);
+    }
+}
+
+pub(crate) fn draw_title_screen(
+    screen_w: f32,
+    screen_h: f32,
+    level_load_errors: &[String],
+    font: Option<&Font>,
+) -> Option<AppScene> {
     clear_background(TITLE_SCREEN_BG);
 
     let title = "Parabolic Rust";
@@ -371,7 +512,7 @@ pub(crate) fn draw_title_screen(screen_w: f32, screen_h: f32, font: Option<&Font
         font,
     );
 
-    let hint = "Click button or press Enter/Space";
+    let hint = "Click button or press Enter/Space | E opens the level editor";
     let hint_size: u16 = 22;
     let hint_measure = measure_text(hint, font, hint_size, 1.0);
     draw_ui_text(
@@ -383,11 +524,42 @@ pub(crate) fn draw_title_screen(screen_w: f32, screen_h: f32, font: Option<&Font
         font,
     );
 
+    if !level_load_errors.is_empty() {
+        let error_color = Color::from_rgba(185, 28, 28, 255);
+        let mut y = button_rect.y + button_rect.h + 90.0;
+        draw_ui_text(
+            "Level files in assets/levels have problems; playing the built-in campaign:",
+            60.0,
+            y,
+            22,
+            error_color,
+            font,
+        );
+        for error in level_load_errors.iter().take(MAX_TITLE_ERRORS) {
+            y += 26.0;
+            draw_ui_text(error, 80.0, y, 18, error_color, font);
+        }
+        if level_load_errors.len() > MAX_TITLE_ERRORS {
+            y += 26.0;
+            let more = format!(
+                "... and {} more",
+                level_load_errors.len() - MAX_TITLE_ERRORS
+            );
+            draw_ui_text(&more, 80.0, y, 18, error_color, font);
+        }
+    }
+
     let mouse = mouse_position();
     let mouse_vec = vec2(mouse.0, mouse.1);
     let clicked_start =
         is_mouse_button_pressed(MouseButton::Left) && button_rect.contains(mouse_vec);
     let hotkey_start = is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space);
 
-    clicked_start || hotkey_start
+    if clicked_start || hotkey_start {
+        Some(AppScene::Game)
+    } else if is_key_pressed(KeyCode::E) {
+        Some(AppScene::Editor)
+    } else {
+        None
+    }
 }
@@ -1,4 +1,12 @@
-use crate::model::{AppScene, GameState, LaunchConfig, LaunchEditor, Level, SurfaceEditor};
+use parabolic_rust::core::integrate::Integrator;
+use parabolic_rust::core::sim::{SHOT_MAX_STEPS, simulate_shot};
+use parabolic_rust::core::solver::NearestSearch;
+
+use crate::constants::HINT_EVALUATIONS_PER_FRAME;
+
+use crate::model::{
+    AppScene, GameState, LaunchConfig, LaunchEditor, Level, LevelEditor, Prediction, SurfaceEditor,
+};
 
 pub(crate) struct AppRuntime {
     pub(crate) levels: Vec<Level>,
@@ -8,16 +16,36 @@ pub(crate) struct AppRuntime {
     pub(crate) game: GameState,
     pub(crate) show_preview: bool,
     pub(crate) sim_speed: f32,
+    pub(crate) integrator: Integrator,
     pub(crate) scene: AppScene,
     pub(crate) surface_editor: SurfaceEditor,
     pub(crate) launch_editor: LaunchEditor,
+    /// Winning arc found by the last hint, drawn as a ghost until the level changes.
+    pub(crate) hint_arc: Option<Prediction>,
+    /// Hint search in progress, advanced a few trial flights per frame.
+    pub(crate) hint_search: Option<NearestSearch>,
+    /// Hint uses per level index, for scoring.
+    pub(crate) hints_used: Vec<u32>,
+    /// Problems found in `assets/levels/`; non-empty means the built-in campaign is in use.
+    pub(crate) level_load_errors: Vec<String>,
+    /// Level being edited; kept while test-playing so the editor resumes where it was.
+    pub(crate) editor: Option<LevelEditor>,
+    /// The campaign put aside while the edited level is test-played.
+    pub(crate) test_play: Option<CampaignBackup>,
+}
+
+pub(crate) struct CampaignBackup {
+    levels: Vec<Level>,
+    current_level_idx: usize,
+    highest_unlocked_level: usize,
+    hints_used: Vec<u32>,
 }
 
 impl AppRuntime {
-    pub(crate) fn new() -> Self {
-        let levels = Level::campaign();
+    pub(crate) fn new(levels: Vec<Level>, level_load_errors: Vec<String>) -> Self {
         let current_level_idx = 0usize;
         let config = levels[current_level_idx].default_launch;
+        let hints_used = vec![0; levels.len()];
         Self {
             levels,
             current_level_idx,
@@ -26,9 +54,16 @@ impl AppRuntime {
             game: GameState::new(),
             show_preview: true,
             sim_speed: 1.0,
+            integrator: Integrator::SemiImplicitEuler,
             scene: AppScene::Title,
             surface_editor: SurfaceEditor::new(),
             launch_editor: LaunchEditor::new(),
+            hint_arc: None,
+            hint_search: None,
+            hints_used,
+            level_load_errors,
+            editor: None,
+            test_play: None,
         }
     }
 
@@ -42,9 +77,72 @@ impl AppRuntime {
 
     pub(crate) fn load_current_level_defaults(&mut self) {
         self.config = self.current_level().default_launch;
+        self.clear_hint();
         self.game.reset();
     }
 
+    pub(crate) fn current_hints_used(&self) -> u32 {
+        self.hints_used[self.current_level_idx]
+    }
+
+    /// Starts searching for the winning launch nearest the current aim; the search
+    /// runs over the next frames in `advance_hint_search`.
+    pub(crate) fn use_hint(&mut self) {
+        if self.hint_search.is_some() {
+            return;
+        }
+        self.hint_search = Some(NearestSearch::new(
+            self.current_level(),
+            self.config,
+            self.integrator,
+        ));
+        self.game.status_line = "Hint: searching for a winning shot...".to_string();
+    }
+
+    /// Runs a slice of the pending hint search and ghosts its arc once it finishes.
+    /// Only a hint that is actually shown counts against the level.
+    pub(crate) fn advance_hint_search(&mut self) {
+        let Some(search) = self.hint_search.as_mut() else {
+            return;
+        };
+        let Some(result) = search.advance(HINT_EVALUATIONS_PER_FRAME) else {
+            return;
+        };
+        self.hint_search = None;
+        let level = self.current_level();
+        // The whole winning flight, which may run well past the preview's cutoff.
+        self.hint_arc = result
+            .ok()
+            .map(|solution| simulate_shot(solution.config, level, self.integrator, SHOT_MAX_STEPS));
+        self.game.status_line = if self.hint_arc.is_some() {
+            self.hints_used[self.current_level_idx] += 1;
+            format!(
+                "Hint {}: follow the green ghost arc",
+                self.current_hints_used()
+            )
+        } else {
+            "Hint: no winning shot from here - try moving the bounce surface".to_string()
+        };
+    }
+
+    /// Drops the ghost arc and any search still running; both go stale when the
+    /// level or stepping changes.
+    pub(crate) fn clear_hint(&mut self) {
+        self.hint_arc = None;
+        self.hint_search = None;
+    }
+
+    pub(crate) fn cycle_integrator(&mut self) {
+        let all = Integrator::ALL;
+        let idx = all
+            .iter()
+            .position(|integrator| integrator.name() == self.integrator.name())
+            .unwrap_or(0);
+        self.integrator = all[(idx + 1) % all.len()];
+        self.clear_hint();
+        self.game.status_line = format!("Integrator: {}", self.integrator.name());
+    }
+
     pub(crate) fn set_loaded_status(&mut self) {
         self.game.status_line = format!("Loaded {}", self.current_level().code);
     }
@@ -57,3 +155,77 @@ impl AppRuntime {
         self.game.status_line = format!("Advanced to {}", self.current_level().code);
     }
 }
+
+impl AppRuntime {
+    /// Opens the editor: back to the edited level after a test-play, otherwise on a
+    /// copy of the level being played.
+    pub(crate) fn open_editor(&mut self) {
+        if self.test_play.is_some() {
+            self.end_test_play();
+        } else {
+            self.editor = Some(LevelEditor::new(self.current_level().clone()));
+        }
+        self.scene = AppScene::Editor;
+    }
+
+    pub(crate) fn close_editor(&mut self) {
+        self.editor = None;
+        self.scene = AppScene::Game;
+        self.load_current_level_defaults();
+        self.set_loaded_status();
+    }
+
+    /// Plays the edited level on its own, with the campaign put aside until `open_editor`.
+    pub(crate) fn start_test_play(&mut self) {
+        let Some(editor) = self.editor.as_ref() else {
+            return;
+        };
+        let level = editor.level.clone();
+        self.test_play = Some(CampaignBackup {
+            levels: std::mem::replace(&mut self.levels, vec![level]),
+            current_level_idx: self.current_level_idx,
+            highest_unlocked_level: self.highest_unlocked_level,
+            hints_used: std::mem::replace(&mut self.hints_used, vec![0]),
+        });
+        self.current_level_idx = 0;
+        self.highest_unlocked_level = 0;
+        self.scene = AppScene::Game;
+        self.load_current_level_defaults();
+        self.game.status_line = format!(
+            "Test-playing {} | E returns to the editor",
+            self.current_level().code
+        );
+    }
+
+    fn end_test_play(&mut self) {
+        if let Some(backup) = self.test_play.take() {
+            self.levels = backup.levels;
+            self.current_level_idx = backup.current_level_idx;
+            self.highest_unlocked_level = backup.highest_unlocked_level;
+            self.hints_used = backup.hints_used;
+            self.load_current_level_defaults();
+        }
+    }
+
+    /// Adds a saved level to the running campaign, replacing any level with its code.
+    pub(crate) fn add_saved_level(&mut self, mut level: Level) {
+        let levels = &mut self.levels;
+        match levels
+            .iter()
+            .position(|other| other.code.eq_ignore_ascii_case(&level.code))
+        {
+            Some(idx) => {
+                level.level_in_environment = levels[idx].level_in_environment;
+                levels[idx] = level;
+            }
+            None => {
+                level.level_in_environment = 1 + levels
+                    .iter()
+                    .filter(|other| other.environment.name == level.environment.name)
+                    .count();
+                levels.push(level);
+                self.hints_used.push(0);
+            }
+        }
+    }
+}
@@ -1,54 +1,545 @@
+use crate::core::float::Real;
+use std::fmt;
+
 pub const EARTH_GRAVITY_MPS2: f64 = 9.8;
 
+/// Named gravity presets so the CLI and the game agree on each body's surface gravity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Body {
+    Mercury,
+    Venus,
+    Earth,
+    Moon,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+    Pluto,
+}
+
+impl Body {
+    pub const ALL: [Body; 10] = [
+        Body::Mercury,
+        Body::Venus,
+        Body::Earth,
+        Body::Moon,
+        Body::Mars,
+        Body::Jupiter,
+        Body::Saturn,
+        Body::Uranus,
+        Body::Neptune,
+        Body::Pluto,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Body::Mercury => "Mercury",
+            Body::Venus => "Venus",
+            Body::Earth => "Earth",
+            Body::Moon => "Moon",
+            Body::Mars => "Mars",
+            Body::Jupiter => "Jupiter",
+            Body::Saturn => "Saturn",
+            Body::Uranus => "Uranus",
+            Body::Neptune => "Neptune",
+            Body::Pluto => "Pluto",
+        }
+    }
+
+    pub fn gravity_mps2(self) -> f64 {
+        match self {
+            Body::Mercury => 3.70,
+            Body::Venus => 8.87,
+            Body::Earth => EARTH_GRAVITY_MPS2,
+            Body::Moon => 1.62,
+            Body::Mars => 3.71,
+            Body::Jupiter => 24.79,
+            Body::Saturn => 10.44,
+            Body::Uranus => 8.69,
+            Body::Neptune => 11.15,
+            Body::Pluto => 0.62,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Body> {
+        Self::ALL
+            .into_iter()
+            .find(|body| body.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct LaunchInputs {
     pub angle_deg: f64,
     pub speed_mps: f64,
     pub height_m: f64,
+    pub gravity_mps2: f64,
+    /// Horizontal position of the launch point.
+    pub launch_x_m: f64,
+    /// Elevation the flight lands at; may sit above `height_m` (a raised platform).
+    pub landing_height_m: f64,
+}
+
+impl Default for LaunchInputs {
+    fn default() -> Self {
+        Self {
+            angle_deg: 0.0,
+            speed_mps: 0.0,
+            height_m: 0.0,
+            gravity_mps2: EARTH_GRAVITY_MPS2,
+            launch_x_m: 0.0,
+            landing_height_m: 0.0,
+        }
+    }
+}
+
+/// Why a ballistics calculation could not produce a result. Carries the offending values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BallisticsError {
+    NonFiniteInput {
+        field: &'static str,
+        value: f64,
+    },
+    NegativeSpeed {
+        speed_mps: f64,
+    },
+    NonPositiveGravity {
+        gravity_mps2: f64,
+    },
+    NegativeDrag {
+        coefficient: f64,
+    },
+    NonPositiveMass {
+        mass_kg: f64,
+    },
+    NonPositiveStep {
+        step_s: f64,
+    },
+    UnsortedTerrain {
+        x_m: f64,
+    },
+    NoRealLanding {
+        discriminant: f64,
+    },
+    NegativeLandingTime {
+        time_s: f64,
+    },
+    PeaksBelowGround {
+        height_m: f64,
+    },
+    FlightTimeout {
+        max_time_s: f64,
+    },
+    LaunchBelowGround {
+        clearance_m: f64,
+    },
+    TargetNotDownrange {
+        target_x_m: f64,
+    },
+    OutOfReach {
+        speed_mps: f64,
+        target_x_m: f64,
+        target_y_m: f64,
+    },
+}
+
+impl fmt::Display for BallisticsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFiniteInput { field, value } => {
+                write!(f, "Inputs must be finite numbers ({field} = {value}).")
+            }
+            Self::NegativeSpeed { speed_mps } => {
+                write!(f, "Velocity cannot be negative ({speed_mps}).")
+            }
+            Self::NonPositiveGravity { gravity_mps2 } => {
+                write!(f, "Gravity must be positive ({gravity_mps2}).")
+            }
+            Self::NegativeDrag { coefficient } => {
+                write!(f, "Drag coefficient cannot be negative ({coefficient}).")
+            }
+            Self::NonPositiveMass { mass_kg } => {
+                write!(f, "Projectile mass must be positive ({mass_kg}).")
+            }
+            Self::NonPositiveStep { step_s } => {
+                write!(f, "Integration step must be positive ({step_s}).")
+            }
+            Self::UnsortedTerrain { x_m } => write!(
+                f,
+                "Terrain points must be sorted by x (out of order at x = {x_m})."
+            ),
+            Self::NoRealLanding { discriminant } => write!(
+                f,
+                "No real landing time: vy^2 + 2*g*h is negative ({discriminant})."
+            ),
+            Self::NegativeLandingTime { time_s } => write!(
+                f,
+                "Landing time computed as negative ({time_s}). Check your inputs."
+            ),
+            Self::PeaksBelowGround { height_m } => write!(
+                f,
+                "No real landing time: projectile peaks below ground (y = {height_m})."
+            ),
+            Self::FlightTimeout { max_time_s } => {
+                write!(f, "Projectile stays airborne longer than {max_time_s} s.")
+            }
+            Self::LaunchBelowGround { clearance_m } => write!(
+                f,
+                "Launch point is below the ground ({clearance_m} m clearance)."
+            ),
+            Self::TargetNotDownrange { target_x_m } => write!(
+                f,
+                "Target must be downrange of the launch point (x = {target_x_m} m)."
+            ),
+            Self::OutOfReach {
+                speed_mps,
+                target_x_m,
+                target_y_m,
+            } => write!(
+                f,
+                "Target ({target_x_m}, {target_y_m}) is out of reach at {speed_mps} m/s."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BallisticsError {}
+
+/// (vx, vy) for a launch at `angle_deg` above the horizontal.
+pub fn launch_velocity<T: Real>(angle_deg: T, speed_mps: T) -> (T, T) {
+    let theta = angle_deg.to_radians();
+    (speed_mps * theta.cos(), speed_mps * theta.sin())
 }
 
 pub fn velocity_components(inputs: LaunchInputs) -> (f64, f64) {
-    let theta = inputs.angle_deg.to_radians();
-    let vx = inputs.speed_mps * theta.cos();
-    let vy = inputs.speed_mps * theta.sin();
-    (vx, vy)
+    launch_velocity(inputs.angle_deg, inputs.speed_mps)
 }
 
 pub fn trajectory_at_time(inputs: LaunchInputs, time_s: f64) -> (f64, f64) {
     let (vx, vy) = velocity_components(inputs);
-    let x = vx * time_s;
-    let y = inputs.height_m + (vy * time_s) - (0.5 * EARTH_GRAVITY_MPS2 * time_s * time_s);
+    let x = inputs.launch_x_m + (vx * time_s);
+    let y = inputs.height_m + (vy * time_s) - (0.5 * inputs.gravity_mps2 * time_s * time_s);
     (x, y)
 }
 
-pub fn flight_time_and_range(inputs: LaunchInputs) -> Result<(f64, f64), String> {
-    if !inputs.angle_deg.is_finite()
-        || !inputs.speed_mps.is_finite()
-        || !inputs.height_m.is_finite()
-    {
-        return Err("Inputs must be finite numbers.".to_string());
+/// Rejects the first named value that is NaN or infinite.
+pub(crate) fn ensure_finite(values: &[(&'static str, f64)]) -> Result<(), BallisticsError> {
+    match values.iter().find(|(_, value)| !value.is_finite()) {
+        Some(&(field, value)) => Err(BallisticsError::NonFiniteInput { field, value }),
+        None => Ok(()),
     }
+}
+
+pub(crate) fn validate_inputs(inputs: LaunchInputs) -> Result<(), BallisticsError> {
+    ensure_finite(&[
+        ("angle_deg", inputs.angle_deg),
+        ("speed_mps", inputs.speed_mps),
+        ("height_m", inputs.height_m),
+        ("gravity_mps2", inputs.gravity_mps2),
+        ("launch_x_m", inputs.launch_x_m),
+        ("landing_height_m", inputs.landing_height_m),
+    ])?;
     if inputs.speed_mps < 0.0 {
-        return Err("Velocity cannot be negative.".to_string());
+        return Err(BallisticsError::NegativeSpeed {
+            speed_mps: inputs.speed_mps,
+        });
+    }
+    if inputs.gravity_mps2 <= 0.0 {
+        return Err(BallisticsError::NonPositiveGravity {
+            gravity_mps2: inputs.gravity_mps2,
+        });
     }
+    Ok(())
+}
 
-    let (_, vy) = velocity_components(inputs);
-    let disc = vy * vy + 2.0 * EARTH_GRAVITY_MPS2 * inputs.height_m;
+/// Time until the flight comes down through `landing_height_m`, and the horizontal distance
+/// covered from the launch point by then.
+pub fn flight_time_and_range(inputs: LaunchInputs) -> Result<(f64, f64), BallisticsError> {
+    validate_inputs(inputs)?;
+
+    let g = inputs.gravity_mps2;
+    let (vx, vy) = velocity_components(inputs);
+    let drop = inputs.height_m - inputs.landing_height_m;
+    let disc = vy * vy + 2.0 * g * drop;
     if disc < 0.0 {
-        return Err(format!(
-            "No real landing time: vy^2 + 2*g*h is negative ({disc})."
-        ));
+        return Err(BallisticsError::NoRealLanding { discriminant: disc });
     }
 
-    let t_land = (vy + disc.sqrt()) / EARTH_GRAVITY_MPS2;
+    // The larger root is the descending crossing; the smaller one is on the way up.
+    let t_land = (vy + disc.sqrt()) / g;
     if t_land < 0.0 {
-        return Err(format!(
-            "Landing time computed as negative ({t_land}). Check your inputs."
-        ));
+        return Err(BallisticsError::NegativeLandingTime { time_s: t_land });
+    }
+
+    Ok((t_land, vx * t_land))
+}
+
+/// Touchdown state of a flight: where, when, and how hard it lands.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlightSummary {
+    pub time_s: f64,
+    pub range_m: f64,
+    pub impact_vx_mps: f64,
+    pub impact_vy_mps: f64,
+    pub impact_speed_mps: f64,
+    /// Angle below the horizontal at touchdown; positive while descending.
+    pub impact_angle_deg: f64,
+    pub kinetic_energy_j: Option<f64>,
+    pub momentum_kg_mps: Option<f64>,
+}
+
+impl FlightSummary {
+    pub fn at_impact(
+        time_s: f64,
+        range_m: f64,
+        impact_vx_mps: f64,
+        impact_vy_mps: f64,
+        mass_kg: Option<f64>,
+    ) -> Self {
+        let impact_speed_mps = impact_vx_mps.hypot(impact_vy_mps);
+        Self {
+            time_s,
+            range_m,
+            impact_vx_mps,
+            impact_vy_mps,
+            impact_speed_mps,
+            impact_angle_deg: (-impact_vy_mps).atan2(impact_vx_mps).to_degrees(),
+            kinetic_energy_j: mass_kg.map(|m| 0.5 * m * impact_speed_mps * impact_speed_mps),
+            momentum_kg_mps: mass_kg.map(|m| m * impact_speed_mps),
+        }
+    }
+}
+
+pub(crate) fn validate_mass(mass_kg: Option<f64>) -> Result<(), BallisticsError> {
+    match mass_kg {
+        Some(mass_kg) if !mass_kg.is_finite() || mass_kg <= 0.0 => {
+            Err(BallisticsError::NonPositiveMass { mass_kg })
+        }
+        _ => Ok(()),
     }
+}
 
-    let (range, _) = trajectory_at_time(inputs, t_land);
-    Ok((t_land, range))
+pub fn flight_summary(
+    inputs: LaunchInputs,
+    mass_kg: Option<f64>,
+) -> Result<FlightSummary, BallisticsError> {
+    validate_mass(mass_kg)?;
+    let (time_s, range_m) = flight_time_and_range(inputs)?;
+    let (vx, vy) = velocity_components(inputs);
+    Ok(FlightSummary::at_impact(
+        time_s,
+        range_m,
+        vx,
+        vy - (inputs.gravity_mps2 * time_s),
+        mass_kg,
+    ))
+}
+
+/// Highest point of the flight. Shots launched level or downward peak at the launch point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Apex {
+    pub time_s: f64,
+    pub x_m: f64,
+    pub height_m: f64,
+}
+
+pub fn apex(inputs: LaunchInputs) -> Result<Apex, BallisticsError> {
+    validate_inputs(inputs)?;
+
+    let (_, vy) = velocity_components(inputs);
+    let time_s = (vy / inputs.gravity_mps2).max(0.0);
+    let (x_m, height_m) = trajectory_at_time(inputs, time_s);
+    Ok(Apex {
+        time_s,
+        x_m,
+        height_m,
+    })
+}
+
+/// Low and high launch angles whose arcs pass through the same target point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AngleSolutions {
+    pub low_deg: f64,
+    pub high_deg: f64,
+    pub low_time_s: f64,
+    pub high_time_s: f64,
+}
+
+pub fn launch_angles_to_target(
+    speed_mps: f64,
+    height_m: f64,
+    target_x_m: f64,
+    target_y_m: f64,
+    gravity_mps2: f64,
+) -> Result<AngleSolutions, BallisticsError> {
+    ensure_finite(&[
+        ("speed_mps", speed_mps),
+        ("height_m", height_m),
+        ("target_x_m", target_x_m),
+        ("target_y_m", target_y_m),
+        ("gravity_mps2", gravity_mps2),
+    ])?;
+    if speed_mps < 0.0 {
+        return Err(BallisticsError::NegativeSpeed { speed_mps });
+    }
+    if gravity_mps2 <= 0.0 {
+        return Err(BallisticsError::NonPositiveGravity { gravity_mps2 });
+    }
+    if target_x_m <= 0.0 {
+        return Err(BallisticsError::TargetNotDownrange { target_x_m });
+    }
+
+    // tan(theta) = (v^2 +/- sqrt(v^4 - g(g*x^2 + 2*dy*v^2))) / (g*x)
+    let g = gravity_mps2;
+    let v2 = speed_mps * speed_mps;
+    let dy = target_y_m - height_m;
+    let disc = (v2 * v2) - g * ((g * target_x_m * target_x_m) + (2.0 * dy * v2));
+    if disc < 0.0 || speed_mps == 0.0 {
+        return Err(BallisticsError::OutOfReach {
+            speed_mps,
+            target_x_m,
+            target_y_m,
+        });
+    }
+
+    let root = disc.sqrt();
+    let low = ((v2 - root) / (g * target_x_m)).atan();
+    let high = ((v2 + root) / (g * target_x_m)).atan();
+    Ok(AngleSolutions {
+        low_deg: low.to_degrees(),
+        high_deg: high.to_degrees(),
+        low_time_s: target_x_m / (speed_mps * low.cos()),
+        high_time_s: target_x_m / (speed_mps * high.cos()),
+    })
+}
+
+/// Slowest launch that still passes through a target point, with the angle it needs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinimumSpeedSolution {
+    pub speed_mps: f64,
+    pub angle_deg: f64,
+    pub time_s: f64,
+}
+
+pub fn minimum_speed_to_target(
+    height_m: f64,
+    target_x_m: f64,
+    target_y_m: f64,
+    gravity_mps2: f64,
+) -> Result<MinimumSpeedSolution, BallisticsError> {
+    ensure_finite(&[
+        ("height_m", height_m),
+        ("target_x_m", target_x_m),
+        ("target_y_m", target_y_m),
+        ("gravity_mps2", gravity_mps2),
+    ])?;
+    if gravity_mps2 <= 0.0 {
+        return Err(BallisticsError::NonPositiveGravity { gravity_mps2 });
+    }
+    if target_x_m <= 0.0 {
+        return Err(BallisticsError::TargetNotDownrange { target_x_m });
+    }
+
+    // v_min^2 = g * (dy + |d|), reached where the two angle solutions merge.
+    let dy = target_y_m - height_m;
+    let distance = target_x_m.hypot(dy);
+    let v2 = gravity_mps2 * (dy + distance);
+    let speed_mps = v2.sqrt();
+    let theta = (v2 / (gravity_mps2 * target_x_m)).atan();
+    Ok(MinimumSpeedSolution {
+        speed_mps,
+        angle_deg: theta.to_degrees(),
+        time_s: target_x_m / (speed_mps * theta.cos()),
+    })
+}
+
+/// Launch angle that maximises range for a given speed and launch height, with the range it gives.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OptimalLaunch {
+    pub angle_deg: f64,
+    pub range_m: f64,
+    pub time_s: f64,
+}
+
+pub fn optimal_launch_angle(
+    speed_mps: f64,
+    height_m: f64,
+    gravity_mps2: f64,
+) -> Result<OptimalLaunch, BallisticsError> {
+    let probe = LaunchInputs {
+        speed_mps,
+        height_m,
+        gravity_mps2,
+        ..LaunchInputs::default()
+    };
+    validate_inputs(probe)?;
+
+    // theta* = atan(v / sqrt(v^2 + 2gh)), R* = (v / g) * sqrt(v^2 + 2gh).
+    let g = gravity_mps2;
+    let disc = (speed_mps * speed_mps) + (2.0 * g * height_m);
+    if disc < 0.0 {
+        return Err(BallisticsError::NoRealLanding { discriminant: disc });
+    }
+    let angle_deg = speed_mps.atan2(disc.sqrt()).to_degrees();
+    let (time_s, _) = flight_time_and_range(LaunchInputs { angle_deg, ..probe })?;
+    Ok(OptimalLaunch {
+        angle_deg,
+        range_m: (speed_mps / g) * disc.sqrt(),
+        time_s,
+    })
+}
+
+/// Height of the safety envelope at `x_m`: the highest point any launch angle reaches there.
+/// y = h + v^2 / (2g) - g * x^2 / (2v^2)
+/// At zero speed the envelope is just the launch point; anywhere else is out of reach.
+pub fn safety_envelope_height(
+    speed_mps: f64,
+    height_m: f64,
+    gravity_mps2: f64,
+    x_m: f64,
+) -> Result<f64, BallisticsError> {
+    validate_inputs(LaunchInputs {
+        speed_mps,
+        height_m,
+        gravity_mps2,
+        ..LaunchInputs::default()
+    })?;
+    ensure_finite(&[("x_m", x_m)])?;
+    let v2 = speed_mps * speed_mps;
+    let peak = height_m + (v2 / (2.0 * gravity_mps2));
+    if x_m == 0.0 {
+        return Ok(peak);
+    }
+    if v2 == 0.0 {
+        return Err(BallisticsError::OutOfReach {
+            speed_mps,
+            target_x_m: x_m,
+            target_y_m: height_m,
+        });
+    }
+    Ok(peak - (gravity_mps2 * x_m * x_m / (2.0 * v2)))
+}
+
+/// Samples the safety envelope from the launch line out to where it meets the ground.
+pub fn sample_safety_envelope(
+    speed_mps: f64,
+    height_m: f64,
+    gravity_mps2: f64,
+    samples: usize,
+) -> Result<Vec<(f64, f64)>, BallisticsError> {
+    // The envelope reaches the ground exactly at the maximum range.
+    let reach = optimal_launch_angle(speed_mps, height_m, gravity_mps2)?;
+    let sample_count = samples.max(2);
+    (0..=sample_count)
+        .map(|i| {
+            let x = (i as f64 * reach.range_m) / sample_count as f64;
+            Ok((
+                x,
+                safety_envelope_height(speed_mps, height_m, gravity_mps2, x)?,
+            ))
+        })
+        .collect()
 }
 
 pub fn sample_trajectory(
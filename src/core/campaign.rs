@@ -0,0 +1,56 @@
+//! Built-in campaign levels, shared by the game and headless tools. The data lives in
+//! `assets/levels/` and is embedded here so tools work without the asset directory.
+
+use crate::core::level_file::parse_campaign;
+use crate::core::sim::Level;
+
+/// Earth levels draw a wind with this magnitude range (m/s^2) and a random sign.
+pub const EARTH_WIND_RANGE_MPS2: (f32, f32) = (0.20, 0.80);
+
+/// Evenly spaced Earth winds covering the whole random range, both directions,
+/// for checking a level holds up under any wind the game can roll.
+pub fn earth_wind_samples(per_direction: usize) -> Vec<f32> {
+    let (low, high) = EARTH_WIND_RANGE_MPS2;
+    let magnitudes: Vec<f32> = match per_direction {
+        0 => vec![],
+        1 => vec![high],
+        n => (0..n)
+            .map(|i| low + (high - low) * (i as f32 / (n - 1) as f32))
+            .collect(),
+    };
+    magnitudes
+        .iter()
+        .rev()
+        .map(|m| -m)
+        .chain(magnitudes.iter().copied())
+        .collect()
+}
+
+// `BUILTIN_LEVEL_FILES`: every level file `assets/levels/campaign.toml` lists, in
+// manifest order, as compiled in. Generated by `build.rs` from the manifest itself.
+include!(concat!(env!("OUT_DIR"), "/builtin_levels.rs"));
+
+/// The built-in level files in manifest order, ready for `parse_campaign`.
+pub fn builtin_campaign_files() -> Vec<(String, String)> {
+    BUILTIN_LEVEL_FILES
+        .iter()
+        .map(|(name, text)| (name.to_string(), text.to_string()))
+        .collect()
+}
+
+impl Level {
+    /// Every built-in campaign level in play order. `earth_wind_mps2` is called once per
+    /// level whose file asks for random wind, so the game can roll it while headless
+    /// tools pass a fixed value.
+    pub fn campaign(earth_wind_mps2: impl FnMut() -> f32) -> Vec<Self> {
+        parse_campaign(&builtin_campaign_files(), earth_wind_mps2)
+            .expect("built-in campaign level files are valid")
+    }
+
+    /// Looks up a campaign level by its code (e.g. "EARTH 3"), ignoring case.
+    pub fn find<'a>(levels: &'a [Level], code: &str) -> Option<&'a Level> {
+        levels
+            .iter()
+            .find(|level| level.code.eq_ignore_ascii_case(code.trim()))
+    }
+}
@@ -0,0 +1,510 @@
+use crate::core::ballistics::{
+    Apex, BallisticsError, FlightSummary, LaunchInputs, OptimalLaunch, apex, ensure_finite,
+    flight_summary, flight_time_and_range, optimal_launch_angle, trajectory_at_time,
+    validate_inputs, validate_mass, velocity_components,
+};
+use crate::core::integrate::{FlightState, Integrator};
+
+pub(crate) const QUADRATIC_STEP_S: f64 = 1.0 / 1000.0;
+pub(crate) const MAX_FLIGHT_TIME_S: f64 = 3600.0;
+pub(crate) const LANDING_BISECTION_STEPS: usize = 80;
+const OPTIMAL_ANGLE_TOLERANCE_DEG: f64 = 1e-6;
+
+/// Air resistance applied on top of gravity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DragModel {
+    None,
+    /// a = -k * v (k in 1/s), matching the game's `drag_linear`.
+    Linear {
+        k_per_s: f64,
+    },
+    /// a = -k * |v| * v (k in 1/m).
+    Quadratic {
+        k_per_m: f64,
+    },
+}
+
+/// Drag plus a constant horizontal wind acceleration, the same way the game applies wind.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Atmosphere {
+    pub drag: DragModel,
+    pub wind_accel_x_mps2: f64,
+}
+
+impl Atmosphere {
+    pub const VACUUM: Atmosphere = Atmosphere {
+        drag: DragModel::None,
+        wind_accel_x_mps2: 0.0,
+    };
+
+    pub fn is_vacuum(&self) -> bool {
+        self.drag == DragModel::None && self.wind_accel_x_mps2 == 0.0
+    }
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Self::VACUUM
+    }
+}
+
+pub fn acceleration(state: &FlightState, gravity_mps2: f64, atmosphere: Atmosphere) -> (f64, f64) {
+    let (drag_x, drag_y) = match atmosphere.drag {
+        DragModel::None => (0.0, 0.0),
+        DragModel::Linear { k_per_s } => (-k_per_s * state.vx_mps, -k_per_s * state.vy_mps),
+        DragModel::Quadratic { k_per_m } => {
+            let speed = state.vx_mps.hypot(state.vy_mps);
+            (
+                -k_per_m * speed * state.vx_mps,
+                -k_per_m * speed * state.vy_mps,
+            )
+        }
+    };
+    (
+        atmosphere.wind_accel_x_mps2 + drag_x,
+        -gravity_mps2 + drag_y,
+    )
+}
+
+pub(crate) fn validate(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+) -> Result<(), BallisticsError> {
+    let coefficient = match atmosphere.drag {
+        DragModel::None => 0.0,
+        DragModel::Linear { k_per_s } => k_per_s,
+        DragModel::Quadratic { k_per_m } => k_per_m,
+    };
+    ensure_finite(&[
+        ("drag_coefficient", coefficient),
+        ("wind_accel_x_mps2", atmosphere.wind_accel_x_mps2),
+    ])?;
+    if coefficient < 0.0 {
+        return Err(BallisticsError::NegativeDrag { coefficient });
+    }
+    validate_inputs(inputs)
+}
+
+/// Closed-form state for linear drag with constant wind and gravity:
+/// v(t) = v_inf + (v0 - v_inf) * e^(-kt), where v_inf = a / k.
+fn linear_drag_state_at_time(
+    inputs: LaunchInputs,
+    k_per_s: f64,
+    wind_accel_x_mps2: f64,
+    time_s: f64,
+) -> FlightState {
+    let (vx0, vy0) = velocity_components(inputs);
+    let g = inputs.gravity_mps2;
+    let decay = (-k_per_s * time_s).exp();
+    // (1 - e^(-kt)) / k, written with exp_m1 so small k stays accurate.
+    let lag = -(-k_per_s * time_s).exp_m1() / k_per_s;
+
+    let vx_inf = wind_accel_x_mps2 / k_per_s;
+    let vy_inf = -g / k_per_s;
+    FlightState {
+        x_m: inputs.launch_x_m + (vx_inf * time_s) + ((vx0 - vx_inf) * lag),
+        y_m: inputs.height_m + (vy_inf * time_s) + ((vy0 - vy_inf) * lag),
+        vx_mps: vx_inf + ((vx0 - vx_inf) * decay),
+        vy_mps: vy_inf + ((vy0 - vy_inf) * decay),
+    }
+}
+
+fn advance_numerically(
+    mut state: FlightState,
+    gravity_mps2: f64,
+    atmosphere: Atmosphere,
+    integrator: Integrator,
+    step_s: f64,
+    duration_s: f64,
+) -> FlightState {
+    let steps = (duration_s / step_s).ceil().max(1.0) as usize;
+    let dt = duration_s / steps as f64;
+    for _ in 0..steps {
+        state = integrator.advance(state, dt, |s| acceleration(s, gravity_mps2, atmosphere));
+    }
+    state
+}
+
+/// Position at `time_s` under drag and wind. Quadratic drag is integrated from launch.
+pub fn trajectory_at_time_with_drag(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    time_s: f64,
+) -> (f64, f64) {
+    let state = state_at_time_with_drag(inputs, atmosphere, time_s);
+    (state.x_m, state.y_m)
+}
+
+pub fn state_at_time_with_drag(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    time_s: f64,
+) -> FlightState {
+    match atmosphere.drag {
+        DragModel::None => {
+            let (vx0, vy0) = velocity_components(inputs);
+            let (x, y) = trajectory_at_time(inputs, time_s);
+            FlightState {
+                x_m: x + (0.5 * atmosphere.wind_accel_x_mps2 * time_s * time_s),
+                y_m: y,
+                vx_mps: vx0 + (atmosphere.wind_accel_x_mps2 * time_s),
+                vy_mps: vy0 - (inputs.gravity_mps2 * time_s),
+            }
+        }
+        DragModel::Linear { k_per_s } if k_per_s > 0.0 => {
+            linear_drag_state_at_time(inputs, k_per_s, atmosphere.wind_accel_x_mps2, time_s)
+        }
+        DragModel::Linear { .. } => state_at_time_with_drag(
+            inputs,
+            Atmosphere {
+                drag: DragModel::None,
+                ..atmosphere
+            },
+            time_s,
+        ),
+        DragModel::Quadratic { .. } => advance_numerically(
+            FlightState::launch(inputs),
+            inputs.gravity_mps2,
+            atmosphere,
+            Integrator::Rk4,
+            QUADRATIC_STEP_S,
+            time_s,
+        ),
+    }
+}
+
+/// vy(t) = 0 solved from the closed form: t = ln(1 + k*vy0/g) / k.
+fn linear_apex_time(inputs: LaunchInputs, k_per_s: f64) -> f64 {
+    let (_, vy0) = velocity_components(inputs);
+    let g = inputs.gravity_mps2;
+    if k_per_s > 0.0 && vy0 > 0.0 {
+        (k_per_s * vy0 / g).ln_1p() / k_per_s
+    } else {
+        (vy0 / g).max(0.0)
+    }
+}
+
+fn quadratic_apex_time(inputs: LaunchInputs, atmosphere: Atmosphere) -> f64 {
+    let g = inputs.gravity_mps2;
+    let accel = |s: &FlightState| acceleration(s, g, atmosphere);
+    let mut state = FlightState::launch(inputs);
+    let mut time_s = 0.0;
+
+    while state.vy_mps > 0.0 && time_s < MAX_FLIGHT_TIME_S {
+        let next = Integrator::Rk4.advance(state, QUADRATIC_STEP_S, accel);
+        if next.vy_mps <= 0.0 {
+            let (mut lo, mut hi) = (0.0, QUADRATIC_STEP_S);
+            for _ in 0..LANDING_BISECTION_STEPS {
+                let mid = 0.5 * (lo + hi);
+                if Integrator::Rk4.advance(state, mid, accel).vy_mps > 0.0 {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            return time_s + 0.5 * (lo + hi);
+        }
+        state = next;
+        time_s += QUADRATIC_STEP_S;
+    }
+    time_s
+}
+
+/// Drag-aware counterpart of `apex`.
+pub fn apex_with_drag(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+) -> Result<Apex, BallisticsError> {
+    validate(inputs, atmosphere)?;
+
+    let time_s = match atmosphere.drag {
+        DragModel::None if atmosphere.wind_accel_x_mps2 == 0.0 => return apex(inputs),
+        DragModel::None => linear_apex_time(inputs, 0.0),
+        DragModel::Linear { k_per_s } => linear_apex_time(inputs, k_per_s),
+        DragModel::Quadratic { .. } => quadratic_apex_time(inputs, atmosphere),
+    };
+    let (x_m, height_m) = trajectory_at_time_with_drag(inputs, atmosphere, time_s);
+    Ok(Apex {
+        time_s,
+        x_m,
+        height_m,
+    })
+}
+
+fn linear_landing_time(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+) -> Result<f64, BallisticsError> {
+    // Height above the landing elevation.
+    let height_at =
+        |t: f64| state_at_time_with_drag(inputs, atmosphere, t).y_m - inputs.landing_height_m;
+
+    // Past the apex the height only decreases, so the landing root is unique there.
+    let t_apex = match atmosphere.drag {
+        DragModel::Linear { k_per_s } => linear_apex_time(inputs, k_per_s),
+        _ => linear_apex_time(inputs, 0.0),
+    };
+    let apex_clearance_m = height_at(t_apex);
+    if apex_clearance_m < 0.0 {
+        return Err(BallisticsError::PeaksBelowGround {
+            height_m: apex_clearance_m + inputs.landing_height_m,
+        });
+    }
+
+    let mut lo = t_apex;
+    let mut hi = t_apex + 1.0;
+    while height_at(hi) > 0.0 {
+        lo = hi;
+        hi *= 2.0;
+        if hi > MAX_FLIGHT_TIME_S {
+            return Err(BallisticsError::FlightTimeout {
+                max_time_s: MAX_FLIGHT_TIME_S,
+            });
+        }
+    }
+    for _ in 0..LANDING_BISECTION_STEPS {
+        let mid = 0.5 * (lo + hi);
+        if height_at(mid) > 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(0.5 * (lo + hi))
+}
+
+fn validate_step(step_s: f64) -> Result<(), BallisticsError> {
+    if !step_s.is_finite() || step_s <= 0.0 {
+        return Err(BallisticsError::NonPositiveStep { step_s });
+    }
+    Ok(())
+}
+
+fn integrated_landing(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    integrator: Integrator,
+    step_s: f64,
+) -> Result<(f64, FlightState), BallisticsError> {
+    validate(inputs, atmosphere)?;
+    validate_step(step_s)?;
+
+    let g = inputs.gravity_mps2;
+    let floor = inputs.landing_height_m;
+    let accel = |s: &FlightState| acceleration(s, g, atmosphere);
+    let mut state = FlightState::launch(inputs);
+    let mut time_s = 0.0;
+
+    while time_s < MAX_FLIGHT_TIME_S {
+        if state.y_m < floor && state.vy_mps <= 0.0 {
+            return Err(BallisticsError::PeaksBelowGround {
+                height_m: state.y_m,
+            });
+        }
+        let next = integrator.advance(state, step_s, accel);
+        if state.y_m >= floor && next.y_m <= floor && next.vy_mps < 0.0 {
+            // Bisect inside the final step to place the landing precisely.
+            let (mut lo, mut hi) = (0.0, step_s);
+            for _ in 0..LANDING_BISECTION_STEPS {
+                let mid = 0.5 * (lo + hi);
+                if integrator.advance(state, mid, accel).y_m > floor {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            let t_step = 0.5 * (lo + hi);
+            return Ok((time_s + t_step, integrator.advance(state, t_step, accel)));
+        }
+        state = next;
+        time_s += step_s;
+    }
+
+    Err(BallisticsError::FlightTimeout {
+        max_time_s: MAX_FLIGHT_TIME_S,
+    })
+}
+
+/// Steps the flight with `integrator` until it comes back down through `landing_height_m`.
+/// Works for every drag model, so the vacuum case can be checked against the analytic parabola.
+pub fn flight_time_and_range_integrated(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    integrator: Integrator,
+    step_s: f64,
+) -> Result<(f64, f64), BallisticsError> {
+    let (time_s, landed) = integrated_landing(inputs, atmosphere, integrator, step_s)?;
+    Ok((time_s, landed.x_m - inputs.launch_x_m))
+}
+
+pub fn flight_summary_integrated(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    integrator: Integrator,
+    step_s: f64,
+    mass_kg: Option<f64>,
+) -> Result<FlightSummary, BallisticsError> {
+    validate_mass(mass_kg)?;
+    let (time_s, landed) = integrated_landing(inputs, atmosphere, integrator, step_s)?;
+    Ok(FlightSummary::at_impact(
+        time_s,
+        landed.x_m - inputs.launch_x_m,
+        landed.vx_mps,
+        landed.vy_mps,
+        mass_kg,
+    ))
+}
+
+/// Integrated counterpart of `sample_trajectory`.
+pub fn sample_trajectory_integrated(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    integrator: Integrator,
+    step_s: f64,
+    time_of_flight_s: f64,
+    samples: usize,
+) -> Vec<(f64, f64)> {
+    let sample_count = samples.max(2);
+    let sample_dt = time_of_flight_s / sample_count as f64;
+    let mut state = FlightState::launch(inputs);
+    let mut points = Vec::with_capacity(sample_count + 1);
+    points.push((state.x_m, state.y_m));
+    for _ in 0..sample_count {
+        state = advance_numerically(
+            state,
+            inputs.gravity_mps2,
+            atmosphere,
+            integrator,
+            step_s,
+            sample_dt,
+        );
+        points.push((state.x_m, state.y_m));
+    }
+    points
+}
+
+/// Drag-aware counterpart of `flight_time_and_range`.
+pub fn flight_time_and_range_with_drag(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+) -> Result<(f64, f64), BallisticsError> {
+    validate(inputs, atmosphere)?;
+
+    let t_land = match atmosphere.drag {
+        DragModel::None if atmosphere.wind_accel_x_mps2 == 0.0 => {
+            return flight_time_and_range(inputs);
+        }
+        DragModel::None => flight_time_and_range(inputs)?.0,
+        DragModel::Linear { .. } => linear_landing_time(inputs, atmosphere)?,
+        DragModel::Quadratic { .. } => {
+            return flight_time_and_range_integrated(
+                inputs,
+                atmosphere,
+                Integrator::Rk4,
+                QUADRATIC_STEP_S,
+            );
+        }
+    };
+
+    let (x_m, _) = trajectory_at_time_with_drag(inputs, atmosphere, t_land);
+    Ok((t_land, x_m - inputs.launch_x_m))
+}
+
+/// Drag-aware counterpart of `flight_summary`.
+pub fn flight_summary_with_drag(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    mass_kg: Option<f64>,
+) -> Result<FlightSummary, BallisticsError> {
+    if atmosphere.is_vacuum() {
+        return flight_summary(inputs, mass_kg);
+    }
+    validate_mass(mass_kg)?;
+    let (time_s, range_m) = flight_time_and_range_with_drag(inputs, atmosphere)?;
+    let landed = state_at_time_with_drag(inputs, atmosphere, time_s);
+    Ok(FlightSummary::at_impact(
+        time_s,
+        range_m,
+        landed.vx_mps,
+        landed.vy_mps,
+        mass_kg,
+    ))
+}
+
+/// Drag-aware counterpart of `sample_trajectory`.
+pub fn sample_trajectory_with_drag(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    time_of_flight_s: f64,
+    samples: usize,
+) -> Vec<(f64, f64)> {
+    let sample_count = samples.max(2);
+    let sample_dt = time_of_flight_s / sample_count as f64;
+
+    match atmosphere.drag {
+        DragModel::Quadratic { .. } => sample_trajectory_integrated(
+            inputs,
+            atmosphere,
+            Integrator::Rk4,
+            QUADRATIC_STEP_S,
+            time_of_flight_s,
+            samples,
+        ),
+        _ => (0..=sample_count)
+            .map(|i| trajectory_at_time_with_drag(inputs, atmosphere, i as f64 * sample_dt))
+            .collect(),
+    }
+}
+
+/// Drag-aware counterpart of `optimal_launch_angle`.
+/// Golden-section search over 0..90 degrees; range is unimodal in the launch angle there.
+pub fn optimal_launch_angle_with_drag(
+    speed_mps: f64,
+    height_m: f64,
+    gravity_mps2: f64,
+    atmosphere: Atmosphere,
+) -> Result<OptimalLaunch, BallisticsError> {
+    if atmosphere.is_vacuum() {
+        return optimal_launch_angle(speed_mps, height_m, gravity_mps2);
+    }
+    let launch = |angle_deg: f64| LaunchInputs {
+        angle_deg,
+        speed_mps,
+        height_m,
+        gravity_mps2,
+        ..LaunchInputs::default()
+    };
+    validate(launch(0.0), atmosphere)?;
+    let range_at = |angle_deg: f64| -> Result<f64, BallisticsError> {
+        Ok(flight_time_and_range_with_drag(launch(angle_deg), atmosphere)?.1)
+    };
+
+    let inv_phi = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut lo, mut hi) = (0.0, 90.0);
+    let mut a = hi - inv_phi * (hi - lo);
+    let mut b = lo + inv_phi * (hi - lo);
+    let (mut range_a, mut range_b) = (range_at(a)?, range_at(b)?);
+    while hi - lo > OPTIMAL_ANGLE_TOLERANCE_DEG {
+        if range_a < range_b {
+            lo = a;
+            a = b;
+            range_a = range_b;
+            b = lo + inv_phi * (hi - lo);
+            range_b = range_at(b)?;
+        } else {
+            hi = b;
+            b = a;
+            range_b = range_a;
+            a = hi - inv_phi * (hi - lo);
+            range_a = range_at(a)?;
+        }
+    }
+
+    let angle_deg = 0.5 * (lo + hi);
+    let (time_s, range_m) = flight_time_and_range_with_drag(launch(angle_deg), atmosphere)?;
+    Ok(OptimalLaunch {
+        angle_deg,
+        range_m,
+        time_s,
+    })
+}
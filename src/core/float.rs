@@ -0,0 +1,19 @@
+pub use num_traits::Float;
+
+/// Float type the shared core math runs on: `f64` for the CLI, `f32` for the macroquad game.
+pub trait Real: Float {
+    /// Converts an `f64` constant into this type, rounding if needed.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Real for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Real for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
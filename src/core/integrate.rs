@@ -0,0 +1,243 @@
+use crate::core::ballistics::{LaunchInputs, velocity_components};
+
+const DORMAND_PRINCE_MIN_STEP_S: f64 = 1e-9;
+const DORMAND_PRINCE_MAX_SUBSTEPS: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlightState {
+    pub x_m: f64,
+    pub y_m: f64,
+    pub vx_mps: f64,
+    pub vy_mps: f64,
+}
+
+impl FlightState {
+    pub fn launch(inputs: LaunchInputs) -> Self {
+        let (vx, vy) = velocity_components(inputs);
+        Self {
+            x_m: inputs.launch_x_m,
+            y_m: inputs.height_m,
+            vx_mps: vx,
+            vy_mps: vy,
+        }
+    }
+
+    fn to_array(self) -> [f64; 4] {
+        [self.x_m, self.y_m, self.vx_mps, self.vy_mps]
+    }
+
+    fn from_array(values: [f64; 4]) -> Self {
+        Self {
+            x_m: values[0],
+            y_m: values[1],
+            vx_mps: values[2],
+            vy_mps: values[3],
+        }
+    }
+}
+
+/// Time-stepping scheme used to advance a `FlightState` under an acceleration field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Integrator {
+    /// Explicit Euler: position moves with the velocity from the start of the step.
+    Euler,
+    /// Semi-implicit Euler: velocity is updated first, then position. The game's original scheme.
+    SemiImplicitEuler,
+    /// Classic fourth-order Runge-Kutta.
+    Rk4,
+    /// Adaptive Dormand-Prince 5(4); substeps shrink until the error estimate fits `tolerance`.
+    DormandPrince { tolerance: f64 },
+}
+
+impl Integrator {
+    pub const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+    pub const ALL: [Integrator; 4] = [
+        Integrator::Euler,
+        Integrator::SemiImplicitEuler,
+        Integrator::Rk4,
+        Integrator::DormandPrince {
+            tolerance: Self::DEFAULT_TOLERANCE,
+        },
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Integrator::Euler => "euler",
+            Integrator::SemiImplicitEuler => "semi-implicit",
+            Integrator::Rk4 => "rk4",
+            Integrator::DormandPrince { .. } => "rk45",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Integrator> {
+        Self::ALL
+            .into_iter()
+            .find(|integrator| integrator.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Advances `state` by `dt` seconds. `accel` returns (ax, ay) for a given state.
+    pub fn advance<F>(self, state: FlightState, dt: f64, accel: F) -> FlightState
+    where
+        F: Fn(&FlightState) -> (f64, f64),
+    {
+        let derive = |values: [f64; 4]| {
+            let (ax, ay) = accel(&FlightState::from_array(values));
+            [values[2], values[3], ax, ay]
+        };
+
+        match self {
+            Integrator::Euler => {
+                let values = state.to_array();
+                FlightState::from_array(offset(values, &[(1.0, derive(values))], dt))
+            }
+            Integrator::SemiImplicitEuler => {
+                let (ax, ay) = accel(&state);
+                let vx_mps = state.vx_mps + (ax * dt);
+                let vy_mps = state.vy_mps + (ay * dt);
+                FlightState {
+                    x_m: state.x_m + (vx_mps * dt),
+                    y_m: state.y_m + (vy_mps * dt),
+                    vx_mps,
+                    vy_mps,
+                }
+            }
+            Integrator::Rk4 => FlightState::from_array(rk4_step(state.to_array(), dt, &derive)),
+            Integrator::DormandPrince { tolerance } => {
+                FlightState::from_array(dormand_prince(state.to_array(), dt, tolerance, &derive))
+            }
+        }
+    }
+}
+
+fn offset(values: [f64; 4], terms: &[(f64, [f64; 4])], h: f64) -> [f64; 4] {
+    let mut out = values;
+    for (weight, k) in terms {
+        for i in 0..4 {
+            out[i] += h * weight * k[i];
+        }
+    }
+    out
+}
+
+fn rk4_step(values: [f64; 4], h: f64, derive: &impl Fn([f64; 4]) -> [f64; 4]) -> [f64; 4] {
+    let k1 = derive(values);
+    let k2 = derive(offset(values, &[(0.5, k1)], h));
+    let k3 = derive(offset(values, &[(0.5, k2)], h));
+    let k4 = derive(offset(values, &[(1.0, k3)], h));
+    offset(
+        values,
+        &[
+            (1.0 / 6.0, k1),
+            (2.0 / 6.0, k2),
+            (2.0 / 6.0, k3),
+            (1.0 / 6.0, k4),
+        ],
+        h,
+    )
+}
+
+/// One Dormand-Prince step: returns the 5th-order solution and the 4th/5th-order difference.
+fn dormand_prince_step(
+    values: [f64; 4],
+    h: f64,
+    derive: &impl Fn([f64; 4]) -> [f64; 4],
+) -> ([f64; 4], [f64; 4]) {
+    let k1 = derive(values);
+    let k2 = derive(offset(values, &[(1.0 / 5.0, k1)], h));
+    let k3 = derive(offset(values, &[(3.0 / 40.0, k1), (9.0 / 40.0, k2)], h));
+    let k4 = derive(offset(
+        values,
+        &[(44.0 / 45.0, k1), (-56.0 / 15.0, k2), (32.0 / 9.0, k3)],
+        h,
+    ));
+    let k5 = derive(offset(
+        values,
+        &[
+            (19372.0 / 6561.0, k1),
+            (-25360.0 / 2187.0, k2),
+            (64448.0 / 6561.0, k3),
+            (-212.0 / 729.0, k4),
+        ],
+        h,
+    ));
+    let k6 = derive(offset(
+        values,
+        &[
+            (9017.0 / 3168.0, k1),
+            (-355.0 / 33.0, k2),
+            (46732.0 / 5247.0, k3),
+            (49.0 / 176.0, k4),
+            (-5103.0 / 18656.0, k5),
+        ],
+        h,
+    ));
+    let fifth = offset(
+        values,
+        &[
+            (35.0 / 384.0, k1),
+            (500.0 / 1113.0, k3),
+            (125.0 / 192.0, k4),
+            (-2187.0 / 6784.0, k5),
+            (11.0 / 84.0, k6),
+        ],
+        h,
+    );
+    let k7 = derive(fifth);
+    let error = offset(
+        [0.0; 4],
+        &[
+            (35.0 / 384.0 - 5179.0 / 57600.0, k1),
+            (500.0 / 1113.0 - 7571.0 / 16695.0, k3),
+            (125.0 / 192.0 - 393.0 / 640.0, k4),
+            (-2187.0 / 6784.0 + 92097.0 / 339200.0, k5),
+            (11.0 / 84.0 - 187.0 / 2100.0, k6),
+            (-1.0 / 40.0, k7),
+        ],
+        h,
+    );
+    (fifth, error)
+}
+
+fn dormand_prince(
+    mut values: [f64; 4],
+    dt: f64,
+    tolerance: f64,
+    derive: &impl Fn([f64; 4]) -> [f64; 4],
+) -> [f64; 4] {
+    let tolerance = tolerance.max(f64::EPSILON);
+    let mut elapsed = 0.0;
+    let mut h = dt;
+
+    for _ in 0..DORMAND_PRINCE_MAX_SUBSTEPS {
+        let remaining = dt - elapsed;
+        if remaining <= 0.0 {
+            break;
+        }
+        h = h.min(remaining);
+
+        let (candidate, error) = dormand_prince_step(values, h, derive);
+        let error_norm = (0..4)
+            .map(|i| error[i].abs() / (tolerance * (1.0 + values[i].abs().max(candidate[i].abs()))))
+            .fold(0.0, f64::max);
+
+        if error_norm <= 1.0 || h <= DORMAND_PRINCE_MIN_STEP_S {
+            values = candidate;
+            elapsed += h;
+        }
+        let factor = if error_norm > 0.0 {
+            0.9 * error_norm.powf(-0.2)
+        } else {
+            5.0
+        };
+        h = (h * factor.clamp(0.2, 5.0)).max(DORMAND_PRINCE_MIN_STEP_S);
+    }
+
+    // Out of substeps: finish with one step over whatever is left, so the caller
+    // always gets a state at `dt` (less accurate, but never a silently short step).
+    let remaining = dt - elapsed;
+    if remaining > 0.0 {
+        values = dormand_prince_step(values, remaining, derive).0;
+    }
+    values
+}
@@ -0,0 +1,1008 @@
+//! TOML level files: parsing, validation and campaign loading.
+//! A campaign is a `campaign.toml` manifest listing level files in play order.
+
+use std::fmt;
+
+use glam::{Vec2, vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::core::ballistics::Body;
+use crate::core::sim::{
+    Barrier, BounceSurface, DEFAULT_SURFACE_DAMPING, Environment, LaunchConfig, Level, Rect,
+    SurfaceMotion, Target, TargetMotion, is_convex_polygon,
+};
+
+/// Directory the game loads campaigns from, relative to the working directory.
+pub const LEVELS_DIR: &str = "assets/levels";
+pub const CAMPAIGN_MANIFEST: &str = "campaign.toml";
+
+/// A level file that could not be loaded, with the reason.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelFileError {
+    pub file: String,
+    pub message: String,
+}
+
+impl LevelFileError {
+    fn new(file: &str, message: impl Into<String>) -> Self {
+        Self {
+            file: file.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl LevelFileError {
+    /// One-line form for places that cannot show the multi-line TOML snippet:
+    /// the location line plus the reason.
+    pub fn summary(&self) -> String {
+        let lines: Vec<&str> = self.message.lines().map(str::trim).collect();
+        match (lines.first(), lines.last()) {
+            (Some(first), Some(last)) if first != last => {
+                format!("{}: {first} - {last}", self.file)
+            }
+            _ => format!("{}: {}", self.file, self.message.trim()),
+        }
+    }
+}
+
+impl fmt::Display for LevelFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file, self.message.trim_end())
+    }
+}
+
+impl std::error::Error for LevelFileError {}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    levels: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct LevelFile {
+    code: String,
+    title: String,
+    #[serde(default)]
+    required_bounces: u32,
+    environment: EnvironmentFile,
+    target: TargetFile,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bounce_surfaces: Vec<BounceSurfaceFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    barriers: Vec<BarrierFile>,
+    default_launch: LaunchFile,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct EnvironmentFile {
+    body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gravity_mps2: Option<f32>,
+    #[serde(default)]
+    wind_mps2: WindFile,
+    #[serde(default)]
+    drag_linear: f32,
+}
+
+/// Either a fixed wind in m/s^2 or `"random"`, rolled per play-through.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum WindFile {
+    Fixed(f32),
+    Keyword(String),
+}
+
+impl Default for WindFile {
+    fn default() -> Self {
+        WindFile::Fixed(0.0)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct TargetFile {
+    center: [f32; 2],
+    radius_m: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    motion: Option<TargetMotionFile>,
+}
+
+/// `[target.motion]`: `kind` is `"linear"` (`offset`), `"circular"` (`orbit_radius_m`)
+/// or `"spline"` (`waypoints`), each with `period_s` and an optional `phase`.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct TargetMotionFile {
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<[f32; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    orbit_radius_m: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    waypoints: Option<Vec<[f32; 2]>>,
+    period_s: f32,
+    #[serde(default)]
+    phase: f32,
+}
+
+impl TargetMotionFile {
+    fn to_motion(&self) -> Result<TargetMotion, String> {
+        let given = [
+            ("offset", self.offset.is_some()),
+            ("orbit_radius_m", self.orbit_radius_m.is_some()),
+            ("waypoints", self.waypoints.is_some()),
+        ];
+        let expected = match self.kind.as_str() {
+            "linear" => "offset",
+            "circular" => "orbit_radius_m",
+            "spline" => "waypoints",
+            other => {
+                return Err(format!(
+                    "target.motion.kind must be \"linear\", \"circular\" or \"spline\" (got \"{other}\")"
+                ));
+            }
+        };
+        for (field, present) in given {
+            if present != (field == expected) {
+                let verb = if present { "does not take" } else { "needs" };
+                return Err(format!(
+                    "target.motion: a {} motion {verb} `{field}`",
+                    self.kind
+                ));
+            }
+        }
+
+        let (period_s, phase) = (self.period_s, self.phase);
+        Ok(match self.kind.as_str() {
+            "linear" => TargetMotion::Linear {
+                offset: point(self.offset.unwrap_or_default()),
+                period_s,
+                phase,
+            },
+            "circular" => TargetMotion::Circular {
+                orbit_radius_m: self.orbit_radius_m.unwrap_or_default(),
+                period_s,
+                phase,
+            },
+            _ => TargetMotion::Spline {
+                waypoints: self
+                    .waypoints
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .map(point)
+                    .collect(),
+                period_s,
+                phase,
+            },
+        })
+    }
+
+    fn from_motion(motion: &TargetMotion) -> Option<Self> {
+        match motion {
+            TargetMotion::Fixed => None,
+            TargetMotion::Linear {
+                offset,
+                period_s,
+                phase,
+            } => Some(Self {
+                kind: "linear".to_string(),
+                offset: Some(offset.to_array()),
+                period_s: *period_s,
+                phase: *phase,
+                ..Self::default()
+            }),
+            TargetMotion::Circular {
+                orbit_radius_m,
+                period_s,
+                phase,
+            } => Some(Self {
+                kind: "circular".to_string(),
+                orbit_radius_m: Some(*orbit_radius_m),
+                period_s: *period_s,
+                phase: *phase,
+                ..Self::default()
+            }),
+            TargetMotion::Spline {
+                waypoints,
+                period_s,
+                phase,
+            } => Some(Self {
+                kind: "spline".to_string(),
+                waypoints: Some(waypoints.iter().map(|p| p.to_array()).collect()),
+                period_s: *period_s,
+                phase: *phase,
+                ..Self::default()
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct BounceSurfaceFile {
+    corners: [[f32; 2]; 4],
+    restitution: f32,
+    #[serde(default)]
+    friction: f32,
+    #[serde(default = "default_damping")]
+    damping: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    motion: Option<SurfaceMotionFile>,
+}
+
+/// `[bounce_surfaces.motion]`: `kind` is `"slide"` (`offset`, `period_s`) or
+/// `"spin"` (`angular_velocity_degps`, counter-clockwise positive).
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct SurfaceMotionFile {
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<[f32; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period_s: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    angular_velocity_degps: Option<f32>,
+}
+
+impl SurfaceMotionFile {
+    fn to_motion(&self, index: usize) -> Result<SurfaceMotion, String> {
+        let label = format!("bounce_surfaces[{index}].motion");
+        let given = [
+            ("offset", self.offset.is_some()),
+            ("period_s", self.period_s.is_some()),
+            (
+                "angular_velocity_degps",
+                self.angular_velocity_degps.is_some(),
+            ),
+        ];
+        let expected: &[&str] = match self.kind.as_str() {
+            "slide" => &["offset", "period_s"],
+            "spin" => &["angular_velocity_degps"],
+            other => {
+                return Err(format!(
+                    "{label}.kind must be \"slide\" or \"spin\" (got \"{other}\")"
+                ));
+            }
+        };
+        for (field, present) in given {
+            if present != expected.contains(&field) {
+                let verb = if present { "does not take" } else { "needs" };
+                return Err(format!("{label}: a {} motion {verb} `{field}`", self.kind));
+            }
+        }
+
+        Ok(match self.kind.as_str() {
+            "slide" => SurfaceMotion::Slide {
+                offset: point(self.offset.unwrap_or_default()),
+                period_s: self.period_s.unwrap_or_default(),
+            },
+            _ => SurfaceMotion::Spin {
+                angular_velocity_radps: self
+                    .angular_velocity_degps
+                    .unwrap_or_default()
+                    .to_radians(),
+            },
+        })
+    }
+
+    fn from_motion(motion: SurfaceMotion) -> Option<Self> {
+        match motion {
+            SurfaceMotion::Fixed => None,
+            SurfaceMotion::Slide { offset, period_s } => Some(Self {
+                kind: "slide".to_string(),
+                offset: Some(offset.to_array()),
+                period_s: Some(period_s),
+                ..Self::default()
+            }),
+            SurfaceMotion::Spin {
+                angular_velocity_radps,
+            } => Some(Self {
+                kind: "spin".to_string(),
+                angular_velocity_degps: Some(angular_velocity_radps.to_degrees()),
+                ..Self::default()
+            }),
+        }
+    }
+}
+
+fn default_damping() -> f32 {
+    DEFAULT_SURFACE_DAMPING
+}
+
+/// One `[[barriers]]` entry: a rectangle (`x`, `y`, `w`, `h`) unless `shape` is
+/// `"polygon"` (`points`) or `"circle"` (`center`, `radius_m`).
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct BarrierFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shape: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    x: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    y: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    w: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    h: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    points: Option<Vec<[f32; 2]>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    center: Option<[f32; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    radius_m: Option<f32>,
+}
+
+impl BarrierFile {
+    fn to_barrier(&self, index: usize) -> Result<Barrier, String> {
+        let shape = self.shape.as_deref().unwrap_or("rect");
+        let given = [
+            ("x", self.x.is_some()),
+            ("y", self.y.is_some()),
+            ("w", self.w.is_some()),
+            ("h", self.h.is_some()),
+            ("points", self.points.is_some()),
+            ("center", self.center.is_some()),
+            ("radius_m", self.radius_m.is_some()),
+        ];
+        let expected: &[&str] = match shape {
+            "rect" => &["x", "y", "w", "h"],
+            "polygon" => &["points"],
+            "circle" => &["center", "radius_m"],
+            other => {
+                return Err(format!(
+                    "barriers[{index}].shape must be \"rect\", \"polygon\" or \"circle\" (got \"{other}\")"
+                ));
+            }
+        };
+        for (field, present) in given {
+            if present != expected.contains(&field) {
+                let verb = if present { "does not take" } else { "needs" };
+                return Err(format!(
+                    "barriers[{index}]: a {shape} barrier {verb} `{field}`"
+                ));
+            }
+        }
+
+        Ok(match shape {
+            "polygon" => {
+                Barrier::Polygon(self.points.iter().flatten().copied().map(point).collect())
+            }
+            "circle" => Barrier::Circle {
+                center: point(self.center.unwrap_or_default()),
+                radius_m: self.radius_m.unwrap_or_default(),
+            },
+            _ => Barrier::Rect(Rect::new(
+                self.x.unwrap_or_default(),
+                self.y.unwrap_or_default(),
+                self.w.unwrap_or_default(),
+                self.h.unwrap_or_default(),
+            )),
+        })
+    }
+
+    fn from_barrier(barrier: &Barrier) -> Self {
+        match barrier {
+            Barrier::Rect(rect) => Self {
+                x: Some(rect.x),
+                y: Some(rect.y),
+                w: Some(rect.w),
+                h: Some(rect.h),
+                ..Self::default()
+            },
+            Barrier::Polygon(points) => Self {
+                shape: Some("polygon".to_string()),
+                points: Some(points.iter().map(|p| p.to_array()).collect()),
+                ..Self::default()
+            },
+            Barrier::Circle { center, radius_m } => Self {
+                shape: Some("circle".to_string()),
+                center: Some(center.to_array()),
+                radius_m: Some(*radius_m),
+                ..Self::default()
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct LaunchFile {
+    angle_deg: f32,
+    speed_mps: f32,
+    height_m: f32,
+}
+
+fn point(xy: [f32; 2]) -> Vec2 {
+    vec2(xy[0], xy[1])
+}
+
+/// Checks one level's values, naming the first offending field.
+fn validate(level: &Level) -> Result<(), String> {
+    if level.code.trim().is_empty() {
+        return Err("code cannot be empty".to_string());
+    }
+    let finite = |label: &str, values: &[f32]| {
+        if values.iter().all(|v| v.is_finite()) {
+            Ok(())
+        } else {
+            Err(format!("{label} must be finite numbers"))
+        }
+    };
+    let env = level.environment;
+    finite(
+        "environment",
+        &[env.gravity_mps2, env.wind_accel_x_mps2, env.drag_linear],
+    )?;
+    if env.gravity_mps2 <= 0.0 {
+        return Err(format!(
+            "environment.gravity_mps2 must be positive (got {})",
+            env.gravity_mps2
+        ));
+    }
+    if env.drag_linear < 0.0 {
+        return Err(format!(
+            "environment.drag_linear cannot be negative (got {})",
+            env.drag_linear
+        ));
+    }
+
+    finite(
+        "target",
+        &[
+            level.target.center.x,
+            level.target.center.y,
+            level.target.radius_m,
+        ],
+    )?;
+    if level.target.radius_m <= 0.0 {
+        return Err(format!(
+            "target.radius_m must be positive (got {})",
+            level.target.radius_m
+        ));
+    }
+    let (period_s, phase, shape) = match &level.target.motion {
+        TargetMotion::Fixed => (1.0, 0.0, Vec::new()),
+        TargetMotion::Linear {
+            offset,
+            period_s,
+            phase,
+        } => (*period_s, *phase, vec![offset.x, offset.y]),
+        TargetMotion::Circular {
+            orbit_radius_m,
+            period_s,
+            phase,
+        } => {
+            if *orbit_radius_m <= 0.0 {
+                return Err(format!(
+                    "target.motion.orbit_radius_m must be positive (got {orbit_radius_m})"
+                ));
+            }
+            (*period_s, *phase, vec![*orbit_radius_m])
+        }
+        TargetMotion::Spline {
+            waypoints,
+            period_s,
+            phase,
+        } => {
+            if waypoints.is_empty() {
+                return Err("target.motion.waypoints must list at least one point".to_string());
+            }
+            let coords = waypoints.iter().flat_map(|p| [p.x, p.y]).collect();
+            (*period_s, *phase, coords)
+        }
+    };
+    finite("target.motion", &[&[period_s, phase][..], &shape].concat())?;
+    if period_s <= 0.0 {
+        return Err(format!(
+            "target.motion.period_s must be positive (got {period_s})"
+        ));
+    }
+
+    for (i, surface) in level.bounce_surfaces.iter().enumerate() {
+        let coords: Vec<f32> = surface.corners.iter().flat_map(|c| [c.x, c.y]).collect();
+        finite(&format!("bounce_surfaces[{i}].corners"), &coords)?;
+        finite(
+            &format!("bounce_surfaces[{i}]"),
+            &[surface.restitution, surface.friction, surface.damping],
+        )?;
+        if !(0.0..=1.0).contains(&surface.restitution) {
+            return Err(format!(
+                "bounce_surfaces[{i}].restitution must be between 0 and 1 (got {})",
+                surface.restitution
+            ));
+        }
+        if surface.friction < 0.0 {
+            return Err(format!(
+                "bounce_surfaces[{i}].friction cannot be negative (got {})",
+                surface.friction
+            ));
+        }
+        if !(0.0..=1.0).contains(&surface.damping) {
+            return Err(format!(
+                "bounce_surfaces[{i}].damping must be between 0 and 1 (got {})",
+                surface.damping
+            ));
+        }
+        match surface.motion {
+            SurfaceMotion::Fixed => {}
+            SurfaceMotion::Slide { offset, period_s } => {
+                finite(
+                    &format!("bounce_surfaces[{i}].motion"),
+                    &[offset.x, offset.y, period_s],
+                )?;
+                if period_s <= 0.0 {
+                    return Err(format!(
+                        "bounce_surfaces[{i}].motion.period_s must be positive (got {period_s})"
+                    ));
+                }
+            }
+            SurfaceMotion::Spin {
+                angular_velocity_radps,
+            } => {
+                finite(
+                    &format!("bounce_surfaces[{i}].motion"),
+                    &[angular_velocity_radps],
+                )?;
+                if angular_velocity_radps == 0.0 {
+                    return Err(format!(
+                        "bounce_surfaces[{i}].motion.angular_velocity_degps cannot be 0; drop the motion for a fixed surface"
+                    ));
+                }
+            }
+        }
+    }
+    if level.bounce_surfaces.is_empty() && level.required_bounces > 0 {
+        return Err(format!(
+            "required_bounces is {} but the level has no [[bounce_surfaces]]",
+            level.required_bounces
+        ));
+    }
+
+    for (i, barrier) in level.barriers.iter().enumerate() {
+        let label = format!("barriers[{i}]");
+        match barrier {
+            Barrier::Rect(rect) => {
+                finite(&label, &[rect.x, rect.y, rect.w, rect.h])?;
+                if rect.w <= 0.0 || rect.h <= 0.0 {
+                    return Err(format!(
+                        "{label} needs a positive width and height (got {} x {})",
+                        rect.w, rect.h
+                    ));
+                }
+            }
+            Barrier::Polygon(points) => {
+                let coords: Vec<f32> = points.iter().flat_map(|p| [p.x, p.y]).collect();
+                finite(&label, &coords)?;
+                if !is_convex_polygon(points) {
+                    return Err(format!(
+                        "{label}.points must form a convex polygon with at least 3 corners"
+                    ));
+                }
+            }
+            Barrier::Circle { center, radius_m } => {
+                finite(&label, &[center.x, center.y, *radius_m])?;
+                if *radius_m <= 0.0 {
+                    return Err(format!(
+                        "{label}.radius_m must be positive (got {radius_m})"
+                    ));
+                }
+            }
+        }
+    }
+
+    let launch = level.default_launch;
+    finite(
+        "default_launch",
+        &[launch.angle_deg, launch.speed_mps, launch.height_m],
+    )?;
+    if launch.speed_mps < 0.0 || launch.height_m < 0.0 {
+        return Err("default_launch.speed_mps and height_m cannot be negative".to_string());
+    }
+
+    Ok(())
+}
+
+/// Parses and validates one level file. `earth_wind_mps2` is called when the file
+/// asks for `wind_mps2 = "random"`. `level_in_environment` is left at 0; campaign
+/// loading numbers levels by their position in the manifest.
+pub fn parse_level(
+    file: &str,
+    text: &str,
+    earth_wind_mps2: &mut impl FnMut() -> f32,
+) -> Result<Level, LevelFileError> {
+    let raw: LevelFile =
+        toml::from_str(text).map_err(|err| LevelFileError::new(file, err.to_string()))?;
+
+    let body = Body::from_name(&raw.environment.body).ok_or_else(|| {
+        let known: Vec<&str> = Body::ALL.iter().map(|body| body.name()).collect();
+        LevelFileError::new(
+            file,
+            format!(
+                "unknown environment.body '{}'; expected one of: {}",
+                raw.environment.body,
+                known.join(", ")
+            ),
+        )
+    })?;
+    let wind_accel_x_mps2 = match raw.environment.wind_mps2 {
+        WindFile::Fixed(wind) => wind,
+        WindFile::Keyword(keyword) if keyword == "random" => earth_wind_mps2(),
+        WindFile::Keyword(keyword) => {
+            return Err(LevelFileError::new(
+                file,
+                format!("environment.wind_mps2 must be a number or \"random\" (got \"{keyword}\")"),
+            ));
+        }
+    };
+
+    let motion = raw
+        .target
+        .motion
+        .as_ref()
+        .map_or(Ok(TargetMotion::Fixed), TargetMotionFile::to_motion)
+        .map_err(|message| LevelFileError::new(file, message))?;
+    let bounce_surfaces = raw
+        .bounce_surfaces
+        .iter()
+        .enumerate()
+        .map(|(i, surface)| {
+            Ok(BounceSurface {
+                corners: surface.corners.map(point),
+                restitution: surface.restitution,
+                friction: surface.friction,
+                damping: surface.damping,
+                motion: surface
+                    .motion
+                    .as_ref()
+                    .map_or(Ok(SurfaceMotion::Fixed), |motion| motion.to_motion(i))?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(|message| LevelFileError::new(file, message))?;
+    let barriers = raw
+        .barriers
+        .iter()
+        .enumerate()
+        .map(|(i, barrier)| barrier.to_barrier(i))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|message| LevelFileError::new(file, message))?;
+
+    let level = Level {
+        code: raw.code,
+        title: raw.title,
+        level_in_environment: 0,
+        environment: Environment {
+            name: body.name(),
+            gravity_mps2: raw
+                .environment
+                .gravity_mps2
+                .unwrap_or(body.gravity_mps2() as f32),
+            wind_accel_x_mps2,
+            drag_linear: raw.environment.drag_linear,
+        },
+        target: Target {
+            center: point(raw.target.center),
+            radius_m: raw.target.radius_m,
+            motion,
+        },
+        bounce_surfaces,
+        barriers,
+        required_bounces: raw.required_bounces,
+        default_launch: LaunchConfig {
+            angle_deg: raw.default_launch.angle_deg,
+            speed_mps: raw.default_launch.speed_mps,
+            height_m: raw.default_launch.height_m,
+        },
+    };
+    validate(&level).map_err(|message| LevelFileError::new(file, message))?;
+    Ok(level)
+}
+
+/// Reads the level file names, in play order, from a campaign manifest.
+pub fn parse_manifest(text: &str) -> Result<Vec<String>, LevelFileError> {
+    let manifest: ManifestFile = toml::from_str(text)
+        .map_err(|err| LevelFileError::new(CAMPAIGN_MANIFEST, err.to_string()))?;
+    if manifest.levels.is_empty() {
+        return Err(LevelFileError::new(
+            CAMPAIGN_MANIFEST,
+            "levels must list at least one level file",
+        ));
+    }
+    Ok(manifest.levels)
+}
+
+/// Parses `(file name, contents)` pairs in play order into a campaign. Every file is
+/// checked, so all problems are reported at once; level codes must be unique.
+pub fn parse_campaign(
+    files: &[(String, String)],
+    mut earth_wind_mps2: impl FnMut() -> f32,
+) -> Result<Vec<Level>, Vec<LevelFileError>> {
+    let mut levels: Vec<Level> = Vec::new();
+    let mut errors = Vec::new();
+
+    for (file, text) in files {
+        match parse_level(file, text, &mut earth_wind_mps2) {
+            Ok(mut level) => {
+                if Level::find(&levels, &level.code).is_some() {
+                    errors.push(LevelFileError::new(
+                        file,
+                        format!("duplicate level code '{}'", level.code),
+                    ));
+                    continue;
+                }
+                level.level_in_environment = 1 + levels
+                    .iter()
+                    .filter(|other| other.environment.name == level.environment.name)
+                    .count();
+                levels.push(level);
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(levels)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Serialises `level` in the level file format. With `random_wind`, the file asks
+/// for the Earth wind roll instead of storing the level's current wind.
+pub fn level_to_toml(level: &Level, random_wind: bool) -> String {
+    let env = level.environment;
+    let body_gravity = Body::from_name(env.name).map(|body| body.gravity_mps2() as f32);
+    let file = LevelFile {
+        code: level.code.clone(),
+        title: level.title.clone(),
+        required_bounces: level.required_bounces,
+        environment: EnvironmentFile {
+            body: env.name.to_string(),
+            gravity_mps2: (body_gravity != Some(env.gravity_mps2)).then_some(env.gravity_mps2),
+            wind_mps2: if random_wind {
+                WindFile::Keyword("random".to_string())
+            } else {
+                WindFile::Fixed(env.wind_accel_x_mps2)
+            },
+            drag_linear: env.drag_linear,
+        },
+        target: TargetFile {
+            center: level.target.center.to_array(),
+            radius_m: level.target.radius_m,
+            motion: TargetMotionFile::from_motion(&level.target.motion),
+        },
+        bounce_surfaces: level
+            .bounce_surfaces
+            .iter()
+            .map(|surface| BounceSurfaceFile {
+                corners: surface.corners.map(|corner| corner.to_array()),
+                restitution: surface.restitution,
+                friction: surface.friction,
+                damping: surface.damping,
+                motion: SurfaceMotionFile::from_motion(surface.motion),
+            })
+            .collect(),
+        barriers: level
+            .barriers
+            .iter()
+            .map(BarrierFile::from_barrier)
+            .collect(),
+        default_launch: LaunchFile {
+            angle_deg: level.default_launch.angle_deg,
+            speed_mps: level.default_launch.speed_mps,
+            height_m: level.default_launch.height_m,
+        },
+    };
+    toml::to_string(&file).expect("level files always serialise")
+}
+
+/// File name for a level code, e.g. "EARTH 5" -> "earth_5.toml".
+pub fn level_file_name(code: &str) -> String {
+    let stem: String = code
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.toml", if stem.is_empty() { "level" } else { &stem })
+}
+
+/// File name to save the level `code` under without clobbering another level:
+/// `level_file_name(code)`, or its stem with `_2`, `_3`, ... while that name is
+/// taken. `stored_code` gives the code held by an existing file (`None` if there is
+/// no such file); a file holding `code` itself is fair to overwrite.
+pub fn free_level_file_name(
+    code: &str,
+    mut stored_code: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let base = level_file_name(code);
+    let stem = base.trim_end_matches(".toml").to_string();
+    std::iter::once(base)
+        .chain((2..).map(|n| format!("{stem}_{n}.toml")))
+        .find(|file| {
+            stored_code(file).is_none_or(|stored| stored.trim().eq_ignore_ascii_case(code.trim()))
+        })
+        .expect("the suffixed names never run out")
+}
+
+/// Returns the manifest text with `file` appended to the play order (unchanged if
+/// already listed). Leading comment lines are kept.
+pub fn manifest_with_level(manifest: &str, file: &str) -> Result<String, LevelFileError> {
+    let mut names = parse_manifest(manifest)?;
+    if names.iter().any(|name| name == file) {
+        return Ok(manifest.to_string());
+    }
+    names.push(file.to_string());
+
+    let mut text: String = manifest
+        .lines()
+        .take_while(|line| line.trim_start().starts_with('#'))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    text.push_str("levels = [\n");
+    for name in names {
+        text.push_str(&format!("    {},\n", toml::Value::String(name)));
+    }
+    text.push_str("]\n");
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Saves and reloads `level`, keeping its campaign position (not stored in the file).
+    fn reload(level: &Level, file: &str) -> (String, Level) {
+        let text = level_to_toml(level, false);
+        let mut reloaded = parse_level(file, &text, &mut || 0.0).expect("saved level parses");
+        reloaded.level_in_environment = level.level_in_environment;
+        (text, reloaded)
+    }
+
+    fn first_level() -> Level {
+        Level::campaign(|| 0.0)[0].clone()
+    }
+
+    #[test]
+    fn saved_levels_reload_unchanged() {
+        for level in Level::campaign(|| 0.35) {
+            let file = level_file_name(&level.code);
+            let random_wind = level.environment.wind_accel_x_mps2 != 0.0;
+            let text = level_to_toml(&level, random_wind);
+            let mut reloaded = parse_level(&file, &text, &mut || 0.35).expect("saved level parses");
+            reloaded.level_in_environment = level.level_in_environment;
+            assert_eq!(reloaded, level, "{text}");
+        }
+        assert_eq!(level_file_name("EARTH 5"), "earth_5.toml");
+
+        // "EARTH-1" maps to EARTH 1's file name, so it must not take that file over.
+        let on_disk = |file: &str| match file {
+            "earth_1.toml" => Some("EARTH 1".to_string()),
+            "earth_1_2.toml" => Some("MOON 9".to_string()),
+            _ => None,
+        };
+        assert_eq!(free_level_file_name("EARTH-1", on_disk), "earth_1_3.toml");
+        assert_eq!(free_level_file_name("earth 1", on_disk), "earth_1.toml");
+        assert_eq!(free_level_file_name("EARTH 5", on_disk), "earth_5.toml");
+
+        let manifest = "# Play order\nlevels = [\"earth_1.toml\"]\n";
+        let updated = manifest_with_level(manifest, "custom_1.toml").unwrap();
+        assert!(updated.starts_with("# Play order\n"), "{updated}");
+        assert_eq!(
+            parse_manifest(&updated).unwrap(),
+            ["earth_1.toml", "custom_1.toml"]
+        );
+        assert_eq!(
+            manifest_with_level(&updated, "custom_1.toml").unwrap(),
+            updated
+        );
+    }
+
+    #[test]
+    fn bounce_surfaces_and_their_motion_reload_unchanged() {
+        let mut level = first_level();
+        let slab = |center: Vec2, motion: SurfaceMotion| BounceSurface {
+            corners: [
+                center + vec2(-40.0, 0.5),
+                center + vec2(40.0, 0.5),
+                center + vec2(40.0, -0.5),
+                center + vec2(-40.0, -0.5),
+            ],
+            restitution: 0.5,
+            friction: 0.2,
+            damping: DEFAULT_SURFACE_DAMPING,
+            motion,
+        };
+        level.bounce_surfaces = vec![
+            slab(vec2(20.0, 10.0), SurfaceMotion::Fixed),
+            slab(
+                vec2(10.0, 10.0),
+                SurfaceMotion::Spin {
+                    angular_velocity_radps: -0.2,
+                },
+            ),
+            slab(
+                vec2(60.0, 40.0),
+                SurfaceMotion::Slide {
+                    offset: vec2(0.0, 15.0),
+                    period_s: 4.0,
+                },
+            ),
+        ];
+
+        let (text, reloaded) = reload(&level, "surfaces.toml");
+        assert_eq!(text.matches("[[bounce_surfaces]]").count(), 3, "{text}");
+        assert_eq!(
+            text.matches("[bounce_surfaces.motion]").count(),
+            2,
+            "{text}"
+        );
+        assert_eq!(reloaded, level, "{text}");
+    }
+
+    #[test]
+    fn barriers_reload_unchanged_and_bad_shapes_are_rejected() {
+        let mut level = first_level();
+        level.barriers = vec![
+            Barrier::Rect(Rect::new(50.7, 0.0, 0.2, 100.0)),
+            Barrier::Circle {
+                center: vec2(50.8, 20.0),
+                radius_m: 0.3,
+            },
+            Barrier::Polygon(vec![vec2(50.7, 0.0), vec2(50.9, 0.0), vec2(50.8, 60.0)]),
+        ];
+
+        let (text, reloaded) = reload(&level, "thin.toml");
+        assert!(text.contains("shape = \"polygon\""), "{text}");
+        assert_eq!(reloaded, level, "{text}");
+
+        let bad = |from: &str, to: &str| {
+            parse_level("thin.toml", &text.replace(from, to), &mut || 0.0)
+                .expect_err("invalid obstacle")
+                .message
+        };
+        assert!(bad("[50.8, 60.0]", "[50.8, 0.0]").contains("convex"));
+        assert!(bad("shape = \"polygon\"", "shape = \"circle\"").contains("points"));
+    }
+
+    #[test]
+    fn target_motion_reloads_unchanged() {
+        let mut level = first_level();
+        let center = level.target.center;
+        for motion in [
+            TargetMotion::Linear {
+                offset: vec2(10.0, -5.0),
+                period_s: 3.0,
+                phase: 0.25,
+            },
+            TargetMotion::Circular {
+                orbit_radius_m: 12.0,
+                period_s: 4.0,
+                phase: 0.0,
+            },
+            TargetMotion::Spline {
+                waypoints: vec![center + vec2(20.0, 10.0), center + vec2(30.0, -4.0)],
+                period_s: 5.0,
+                phase: 0.5,
+            },
+        ] {
+            level.target.motion = motion;
+            let (text, reloaded) = reload(&level, "moving.toml");
+            assert!(text.contains("[target.motion]"), "{text}");
+            assert_eq!(reloaded, level, "{text}");
+        }
+
+        let text = level_to_toml(&level, false).replace("period_s = 5.0", "period_s = 0.0");
+        let err = parse_level("moving.toml", &text, &mut || 0.0).expect_err("zero period");
+        assert!(err.summary().contains("period_s"), "{}", err.summary());
+    }
+}
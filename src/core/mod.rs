@@ -1,2 +1,10 @@
 pub mod ballistics;
+pub mod campaign;
+pub mod drag;
+pub mod float;
+pub mod integrate;
+pub mod level_file;
+pub mod sim;
+pub mod solver;
+pub mod terrain;
 pub mod window;
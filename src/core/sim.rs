@@ -0,0 +1,829 @@
+//! Headless level simulation: projectile stepping, collisions and shot outcomes.
+//! Uses the same `glam::Vec2` as macroquad, so the game can share these types directly.
+
+use glam::{Vec2, vec2};
+
+use crate::core::ballistics;
+use crate::core::drag::{Atmosphere, DragModel, acceleration};
+use crate::core::integrate::{FlightState, Integrator};
+
+pub const FIXED_STEP_S: f32 = 1.0 / 240.0;
+pub const MAX_SIM_TIME_S: f32 = 60.0;
+/// Step budget for `simulate_prediction` (8 s of flight at `FIXED_STEP_S`).
+pub const PREDICTION_MAX_STEPS: usize = 1920;
+/// Step budget for a full shot, matching the live game's `MAX_SIM_TIME_S` cutoff.
+pub const SHOT_MAX_STEPS: usize = 14_400;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaunchConfig {
+    pub angle_deg: f32,
+    pub speed_mps: f32,
+    pub height_m: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Environment {
+    pub name: &'static str,
+    pub gravity_mps2: f32,
+    pub wind_accel_x_mps2: f32,
+    pub drag_linear: f32,
+}
+
+/// Fraction of speed a bounce loses on top of restitution and friction, unless a
+/// surface sets its own.
+pub const DEFAULT_SURFACE_DAMPING: f32 = 0.005;
+/// Ball radius used for spin: only how contact friction trades speed for rotation.
+/// Collisions still treat the projectile as a point.
+pub const PROJECTILE_RADIUS_M: f32 = 0.5;
+
+/// How a bounce surface moves, timed from launch. Every motion starts a flight at
+/// the rest `corners`, so the pose that is edited is the pose a shot starts against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SurfaceMotion {
+    Fixed,
+    /// Eases back and forth between the rest corners and the corners moved by `offset`.
+    Slide {
+        offset: Vec2,
+        period_s: f32,
+    },
+    /// Spins about the centre of the rest corners; positive is counter-clockwise.
+    Spin {
+        angular_velocity_radps: f32,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BounceSurface {
+    /// Rest pose; see `corners_at` for where a moving surface is mid-flight.
+    pub corners: [Vec2; 4],
+    pub restitution: f32,
+    /// Coulomb friction coefficient between the ball and the surface.
+    pub friction: f32,
+    /// Fraction of speed lost on each bounce, applied after restitution and friction.
+    pub damping: f32,
+    pub motion: SurfaceMotion,
+}
+
+impl BounceSurface {
+    /// Centre of the rest corners, which a spinning surface turns about.
+    pub fn pivot(&self) -> Vec2 {
+        self.corners.iter().copied().sum::<Vec2>() * 0.25
+    }
+
+    /// Seconds until the surface is back at rest, or `None` for a fixed surface.
+    pub fn period_s(&self) -> Option<f32> {
+        match self.motion {
+            SurfaceMotion::Fixed => None,
+            SurfaceMotion::Slide { period_s, .. } => Some(period_s),
+            SurfaceMotion::Spin {
+                angular_velocity_radps,
+            } => Some(std::f32::consts::TAU / angular_velocity_radps.abs()),
+        }
+    }
+
+    /// Rotation (about the pivot) and then translation applied to the rest corners
+    /// `time_s` seconds after launch.
+    fn pose_at(&self, time_s: f32) -> (f32, Vec2) {
+        match self.motion {
+            SurfaceMotion::Fixed => (0.0, Vec2::ZERO),
+            SurfaceMotion::Slide { offset, period_s } => {
+                let angle = std::f32::consts::TAU * (time_s / period_s).rem_euclid(1.0);
+                (0.0, offset * (0.5 - (0.5 * angle.cos())))
+            }
+            SurfaceMotion::Spin {
+                angular_velocity_radps,
+            } => (angular_velocity_radps * time_s, Vec2::ZERO),
+        }
+    }
+
+    /// Maps a rest-pose point to where it is `time_s` seconds after launch.
+    pub fn to_world(&self, local: Vec2, time_s: f32) -> Vec2 {
+        if self.motion == SurfaceMotion::Fixed {
+            return local;
+        }
+        let (angle, offset) = self.pose_at(time_s);
+        let pivot = self.pivot();
+        pivot + Vec2::from_angle(angle).rotate(local - pivot) + offset
+    }
+
+    /// Inverse of `to_world`: the rest-pose point under `world` at `time_s`.
+    pub fn to_local(&self, world: Vec2, time_s: f32) -> Vec2 {
+        if self.motion == SurfaceMotion::Fixed {
+            return world;
+        }
+        let (angle, offset) = self.pose_at(time_s);
+        let pivot = self.pivot();
+        pivot + Vec2::from_angle(-angle).rotate(world - offset - pivot)
+    }
+
+    pub fn corners_at(&self, time_s: f32) -> [Vec2; 4] {
+        self.corners.map(|corner| self.to_world(corner, time_s))
+    }
+
+    /// Velocity of the surface material at `world` (a point on or in the surface).
+    pub fn velocity_at(&self, world: Vec2, time_s: f32) -> Vec2 {
+        match self.motion {
+            SurfaceMotion::Fixed => Vec2::ZERO,
+            SurfaceMotion::Slide { offset, period_s } => {
+                let angle = std::f32::consts::TAU * (time_s / period_s).rem_euclid(1.0);
+                offset * (0.5 * angle.sin() * std::f32::consts::TAU / period_s)
+            }
+            SurfaceMotion::Spin {
+                angular_velocity_radps,
+            } => (world - self.pivot()).perp() * angular_velocity_radps,
+        }
+    }
+}
+
+/// How a target moves, timed from launch. `phase` is a fraction of the period
+/// (0..1) added to the clock, so targets need not start at the same point.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TargetMotion {
+    Fixed,
+    /// Eases back and forth between `center` and `center + offset`.
+    Linear {
+        offset: Vec2,
+        period_s: f32,
+        phase: f32,
+    },
+    /// Counter-clockwise orbit around `center`.
+    Circular {
+        orbit_radius_m: f32,
+        period_s: f32,
+        phase: f32,
+    },
+    /// Closed Catmull-Rom loop from `center` through each waypoint and back, spending
+    /// equal time on each leg.
+    Spline {
+        waypoints: Vec<Vec2>,
+        period_s: f32,
+        phase: f32,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    /// Rest position: where a fixed target sits and each motion is anchored.
+    pub center: Vec2,
+    pub radius_m: f32,
+    pub motion: TargetMotion,
+}
+
+fn catmull_rom(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    ((p1 * 2.0)
+        + ((p2 - p0) * t)
+        + (((p0 * 2.0) - (p1 * 5.0) + (p2 * 4.0) - p3) * t2)
+        + (((p1 * 3.0) - p0 - (p2 * 3.0) + p3) * t3))
+        * 0.5
+}
+
+impl Target {
+    pub fn fixed(center: Vec2, radius_m: f32) -> Self {
+        Self {
+            center,
+            radius_m,
+            motion: TargetMotion::Fixed,
+        }
+    }
+
+    /// Loop length in seconds, or `None` for a fixed target.
+    pub fn period_s(&self) -> Option<f32> {
+        match self.motion {
+            TargetMotion::Fixed => None,
+            TargetMotion::Linear { period_s, .. }
+            | TargetMotion::Circular { period_s, .. }
+            | TargetMotion::Spline { period_s, .. } => Some(period_s),
+        }
+    }
+
+    /// Where the target's centre is `time_s` seconds after launch.
+    pub fn position_at(&self, time_s: f32) -> Vec2 {
+        let cycle = |period_s: f32, phase: f32| (time_s / period_s + phase).rem_euclid(1.0);
+        match &self.motion {
+            TargetMotion::Fixed => self.center,
+            TargetMotion::Linear {
+                offset,
+                period_s,
+                phase,
+            } => {
+                let angle = std::f32::consts::TAU * cycle(*period_s, *phase);
+                self.center + (*offset * (0.5 - (0.5 * angle.cos())))
+            }
+            TargetMotion::Circular {
+                orbit_radius_m,
+                period_s,
+                phase,
+            } => {
+                let angle = std::f32::consts::TAU * cycle(*period_s, *phase);
+                self.center + (vec2(angle.cos(), angle.sin()) * *orbit_radius_m)
+            }
+            TargetMotion::Spline {
+                waypoints,
+                period_s,
+                phase,
+            } => {
+                let points: Vec<Vec2> = std::iter::once(self.center)
+                    .chain(waypoints.iter().copied())
+                    .collect();
+                let n = points.len();
+                let u = cycle(*period_s, *phase) * n as f32;
+                let leg = (u.floor() as usize).min(n - 1);
+                let at = |offset: usize| points[(leg + n + offset - 1) % n];
+                catmull_rom(at(0), at(1), at(2), at(3), u - leg as f32)
+            }
+        }
+    }
+
+    /// Moves the target and its whole motion path; spline waypoints are absolute.
+    pub fn translate(&mut self, delta: Vec2) {
+        self.center += delta;
+        if let TargetMotion::Spline { waypoints, .. } = &mut self.motion {
+            for point in waypoints {
+                *point += delta;
+            }
+        }
+    }
+
+    /// One full loop of the motion, for drawing and framing; a fixed target gives
+    /// just its centre.
+    pub fn motion_path(&self, samples: usize) -> Vec<Vec2> {
+        match self.period_s() {
+            None => vec![self.center],
+            Some(period_s) => (0..=samples)
+                .map(|i| self.position_at(period_s * i as f32 / samples.max(1) as f32))
+                .collect(),
+        }
+    }
+}
+
+/// Axis-aligned rectangle in world units; `(x, y)` is the lower-left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Rect {
+    pub const fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Half-open containment, matching macroquad's `Rect::contains`.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.x
+            && point.x < self.x + self.w
+            && point.y >= self.y
+            && point.y < self.y + self.h
+    }
+}
+
+impl Rect {
+    /// Corners counter-clockwise from the lower-left.
+    pub fn corners(&self) -> [Vec2; 4] {
+        [
+            vec2(self.x, self.y),
+            vec2(self.x + self.w, self.y),
+            vec2(self.x + self.w, self.y + self.h),
+            vec2(self.x, self.y + self.h),
+        ]
+    }
+}
+
+/// A solid obstacle; touching one ends the shot with `StepOutcome::HitBarrier`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Barrier {
+    Rect(Rect),
+    /// Convex polygon, corners in order (either winding).
+    Polygon(Vec<Vec2>),
+    Circle {
+        center: Vec2,
+        radius_m: f32,
+    },
+}
+
+impl Barrier {
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self {
+            Barrier::Rect(rect) => rect.contains(point),
+            Barrier::Polygon(points) => point_in_polygon(point, points),
+            Barrier::Circle { center, radius_m } => point.distance(*center) <= *radius_m,
+        }
+    }
+
+    /// Fraction along the step from `from` to `to` where it first touches the obstacle
+    /// (0 if it starts inside), so fast shots cannot tunnel through thin obstacles.
+    pub fn first_contact(&self, from: Vec2, to: Vec2) -> Option<f32> {
+        let edge_contact = |points: &[Vec2]| {
+            polygon_edges(points)
+                .filter_map(|(a, b)| segment_intersection(from, to, a, b).map(|(t, _u)| t))
+                .reduce(f32::min)
+        };
+        let (starts_inside, ends_inside, edge_t) = match self {
+            Barrier::Rect(rect) => (
+                rect.contains(from),
+                rect.contains(to),
+                edge_contact(&rect.corners()),
+            ),
+            Barrier::Polygon(points) => (
+                point_in_polygon(from, points),
+                point_in_polygon(to, points),
+                edge_contact(points),
+            ),
+            Barrier::Circle { center, radius_m } => {
+                return segment_circle_entry(from, to, *center, *radius_m);
+            }
+        };
+        if starts_inside {
+            Some(0.0)
+        } else {
+            // A step ending inside always crosses an edge, barring float error at a corner.
+            edge_t.or(ends_inside.then_some(1.0))
+        }
+    }
+
+    /// Axis-aligned bounding box.
+    pub fn bounds(&self) -> Rect {
+        match self {
+            Barrier::Rect(rect) => *rect,
+            Barrier::Polygon(points) => {
+                let min = points.iter().fold(Vec2::MAX, |acc, p| acc.min(*p));
+                let max = points.iter().fold(Vec2::MIN, |acc, p| acc.max(*p));
+                Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+            }
+            Barrier::Circle { center, radius_m } => Rect::new(
+                center.x - radius_m,
+                center.y - radius_m,
+                radius_m * 2.0,
+                radius_m * 2.0,
+            ),
+        }
+    }
+
+    pub fn translate(&mut self, delta: Vec2) {
+        match self {
+            Barrier::Rect(rect) => {
+                rect.x += delta.x;
+                rect.y += delta.y;
+            }
+            Barrier::Polygon(points) => points.iter_mut().for_each(|p| *p += delta),
+            Barrier::Circle { center, .. } => *center += delta,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub code: String,
+    pub title: String,
+    pub level_in_environment: usize,
+    pub environment: Environment,
+    pub target: Target,
+    /// Banks the shot can bounce off; each has its own restitution.
+    pub bounce_surfaces: Vec<BounceSurface>,
+    pub barriers: Vec<Barrier>,
+    pub required_bounces: u32,
+    pub default_launch: LaunchConfig,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Projectile {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub elapsed_s: f32,
+    pub bounces: u32,
+    /// Spin in rad/s, counter-clockwise positive; changed only by surface friction.
+    pub angular_velocity_radps: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepOutcome {
+    Flying,
+    HitTarget,
+    HitGround,
+    HitBarrier,
+}
+
+impl StepOutcome {
+    pub fn name(self) -> &'static str {
+        match self {
+            StepOutcome::Flying => "flying",
+            StepOutcome::HitTarget => "hit_target",
+            StepOutcome::HitGround => "hit_ground",
+            StepOutcome::HitBarrier => "hit_barrier",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prediction {
+    pub points: Vec<Vec2>,
+    pub range_m: f32,
+    pub flight_time_s: f32,
+    pub bounces: u32,
+    pub outcome: StepOutcome,
+}
+
+pub fn launch_velocity(config: LaunchConfig) -> Vec2 {
+    let (vx, vy) = ballistics::launch_velocity(config.angle_deg, config.speed_mps);
+    vec2(vx, vy)
+}
+
+pub fn launch_projectile(config: LaunchConfig) -> Projectile {
+    Projectile {
+        position: vec2(0.0, config.height_m.max(0.0)),
+        velocity: launch_velocity(config),
+        elapsed_s: 0.0,
+        bounces: 0,
+        angular_velocity_radps: 0.0,
+    }
+}
+
+fn cross_2d(a: Vec2, b: Vec2) -> f32 {
+    (a.x * b.y) - (a.y * b.x)
+}
+
+pub fn segment_intersection(p: Vec2, p2: Vec2, q: Vec2, q2: Vec2) -> Option<(f32, f32)> {
+    let r = p2 - p;
+    let s = q2 - q;
+    let rxs = cross_2d(r, s);
+    let qmp = q - p;
+
+    if rxs.abs() < 1e-6 {
+        return None;
+    }
+
+    let t = cross_2d(qmp, s) / rxs;
+    let u = cross_2d(qmp, r) / rxs;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some((t, u))
+    } else {
+        None
+    }
+}
+
+pub fn point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let pi = polygon[i];
+        let pj = polygon[j];
+        let intersect = ((pi.y > point.y) != (pj.y > point.y))
+            && (point.x < (pj.x - pi.x) * (point.y - pi.y) / (pj.y - pi.y + 1e-6) + pi.x);
+        if intersect {
+            inside = !inside;
+        }
+        j = i;
+    }
+
+    inside
+}
+
+/// Edges of a closed polygon, including the one back to the first point.
+pub fn polygon_edges(points: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// True for a polygon of at least three corners that turns the same way at each one.
+pub fn is_convex_polygon(points: &[Vec2]) -> bool {
+    if points.len() < 3 {
+        return false;
+    }
+    let n = points.len();
+    let turns: Vec<f32> = (0..n)
+        .map(|i| {
+            let a = points[i];
+            let b = points[(i + 1) % n];
+            let c = points[(i + 2) % n];
+            cross_2d(b - a, c - b)
+        })
+        .collect();
+    turns.iter().all(|turn| *turn > 1e-6) || turns.iter().all(|turn| *turn < -1e-6)
+}
+
+/// Fraction along `from`-`to` where the segment first enters the circle, or 0 if it
+/// starts inside.
+pub fn segment_circle_entry(from: Vec2, to: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let d = to - from;
+    let f = from - center;
+    let c = f.length_squared() - (radius * radius);
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let a = d.length_squared();
+    if a < 1e-12 {
+        return None;
+    }
+    let b = 2.0 * f.dot(d);
+    let discriminant = (b * b) - (4.0 * a * c);
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+/// Distance from `point` to the segment `a`-`b`, or to the whole line through them.
+pub fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2, unbounded: bool) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_squared();
+    if length_sq < 1e-12 {
+        return point.distance(a);
+    }
+    let t = (point - a).dot(ab) / length_sq;
+    let t = if unbounded { t } else { t.clamp(0.0, 1.0) };
+    point.distance(a + ab * t)
+}
+
+pub fn bounce_surface_edges(corners: &[Vec2; 4]) -> [(Vec2, Vec2); 4] {
+    [
+        (corners[0], corners[1]),
+        (corners[1], corners[2]),
+        (corners[2], corners[3]),
+        (corners[3], corners[0]),
+    ]
+}
+
+pub fn step_projectile(
+    projectile: &mut Projectile,
+    level: &Level,
+    dt: f32,
+    integrator: Integrator,
+) -> StepOutcome {
+    let env = level.environment;
+    let prev = projectile.position;
+
+    // Wind + linear drag hooks are in place even though Moon level uses zeros.
+    let gravity_mps2 = f64::from(env.gravity_mps2);
+    let atmosphere = Atmosphere {
+        drag: DragModel::Linear {
+            k_per_s: f64::from(env.drag_linear),
+        },
+        wind_accel_x_mps2: f64::from(env.wind_accel_x_mps2),
+    };
+    let state = FlightState {
+        x_m: f64::from(prev.x),
+        y_m: f64::from(prev.y),
+        vx_mps: f64::from(projectile.velocity.x),
+        vy_mps: f64::from(projectile.velocity.y),
+    };
+    let next = integrator.advance(state, f64::from(dt), |s| {
+        acceleration(s, gravity_mps2, atmosphere)
+    });
+    projectile.velocity = vec2(next.vx_mps as f32, next.vy_mps as f32);
+    projectile.position = vec2(next.x_m as f32, next.y_m as f32);
+    projectile.elapsed_s += dt;
+
+    let flown = *projectile;
+    let bounce = resolve_surface_bounce(projectile, &level.bounce_surfaces, prev, dt);
+
+    // Test the whole swept step, not just where it ends, and stop the shot at the
+    // exact contact so hits between frames still count. A bounce splits the step
+    // into the leg into the surface and the leg away from it, each swept with the
+    // shot's state on that leg.
+    // (state, leg start, leg end, start and end as fractions of the step)
+    let legs = match bounce {
+        Some((t, contact)) => [
+            Some((flown, prev, contact, 0.0, t)),
+            Some((*projectile, contact, projectile.position, t, 1.0)),
+        ],
+        None => [
+            Some((*projectile, prev, projectile.position, 0.0, 1.0)),
+            None,
+        ],
+    };
+    for (state, from, to, from_t, to_t) in legs.into_iter().flatten() {
+        let from_s = state.elapsed_s - (dt * (1.0 - from_t));
+        let to_s = state.elapsed_s - (dt * (1.0 - to_t));
+        if let Some((s, outcome)) =
+            first_contact_on_leg(level, state.bounces, from, to, from_s, to_s)
+        {
+            *projectile = state;
+            projectile.position = from + ((to - from) * s);
+            projectile.elapsed_s -= dt * (1.0 - (from_t + ((to_t - from_t) * s)));
+            return outcome;
+        }
+    }
+
+    if projectile.position.y <= 0.0 {
+        projectile.position.y = 0.0;
+        return StepOutcome::HitGround;
+    }
+
+    StepOutcome::Flying
+}
+
+/// First barrier or target the shot meets flying `from`-`to` between `from_s` and
+/// `to_s`, as the fraction along that leg. The target only counts once `bounces`
+/// meets the level's requirement.
+fn first_contact_on_leg(
+    level: &Level,
+    bounces: u32,
+    from: Vec2,
+    to: Vec2,
+    from_s: f32,
+    to_s: f32,
+) -> Option<(f32, StepOutcome)> {
+    let barrier_t = level
+        .barriers
+        .iter()
+        .filter_map(|barrier| barrier.first_contact(from, to))
+        .reduce(f32::min);
+    // Sweep in the target's frame, so a moving target is caught mid-step too.
+    let target_start = level.target.position_at(from_s);
+    let target_end = level.target.position_at(to_s);
+    let target_t = (bounces >= level.required_bounces)
+        .then(|| {
+            segment_circle_entry(
+                from - target_start,
+                to - target_end,
+                Vec2::ZERO,
+                level.target.radius_m,
+            )
+        })
+        .flatten();
+    match (barrier_t, target_t) {
+        (Some(b), Some(t)) if t <= b => Some((t, StepOutcome::HitTarget)),
+        (Some(b), _) => Some((b, StepOutcome::HitBarrier)),
+        (None, Some(t)) => Some((t, StepOutcome::HitTarget)),
+        (None, None) => None,
+    }
+}
+
+/// Bounce response for a solid ball hitting a surface with outward `normal`:
+/// restitution on the normal speed, then a Coulomb friction impulse at the contact
+/// point that trades sliding speed for spin (up to rolling without slip), then the
+/// surface's damping.
+fn apply_bounce_impulse(projectile: &mut Projectile, normal: Vec2, surface: &BounceSurface) {
+    let radius = PROJECTILE_RADIUS_M;
+    let tangent = normal.perp();
+    let vn = projectile.velocity.dot(normal);
+    let normal_impulse = (1.0 + surface.restitution) * -vn;
+    projectile.velocity += normal * normal_impulse;
+
+    // Contact point sits at -radius * normal, so its sliding speed includes the spin.
+    let slip = projectile.velocity.dot(tangent) - (radius * projectile.angular_velocity_radps);
+    // A solid sphere (I = 2/5 m r^2) stops slipping after an impulse of 2/7 m * slip.
+    let friction_impulse = (surface.friction * normal_impulse).min((2.0 / 7.0) * slip.abs());
+    let friction_impulse = friction_impulse.copysign(slip);
+    projectile.velocity -= tangent * friction_impulse;
+    projectile.angular_velocity_radps += 2.5 * friction_impulse / radius;
+
+    projectile.velocity *= 1.0 - surface.damping;
+}
+
+/// Bounces off the first surface edge the step crosses, across all surfaces. Each
+/// surface is tested in its own rest frame, so the step is swept against a moving
+/// surface as well as a fixed one, and the bounce happens relative to the surface's
+/// velocity at the contact point, which a moving surface passes on to the shot.
+/// Only edges the shot is closing on count, so one contact is one bounce. Returns
+/// the fraction of the step at which a swept hit happened and where the ball was.
+fn resolve_surface_bounce(
+    projectile: &mut Projectile,
+    surfaces: &[BounceSurface],
+    prev: Vec2,
+    dt: f32,
+) -> Option<(f32, Vec2)> {
+    let end_s = projectile.elapsed_s;
+    let start_s = end_s - dt;
+    // (t, rest-frame contact, rest-frame normal, world normal, surface velocity, surface)
+    let mut best_hit: Option<(f32, Vec2, Vec2, Vec2, Vec2, &BounceSurface)> = None;
+
+    for surface in surfaces {
+        let local_prev = surface.to_local(prev, start_s);
+        let local_end = surface.to_local(projectile.position, end_s);
+        for (a, b) in bounce_surface_edges(&surface.corners) {
+            let Some((t, _u)) = segment_intersection(local_prev, local_end, a, b) else {
+                continue;
+            };
+            let Some(local_normal) = outward_edge_normal(surface, a, b) else {
+                continue;
+            };
+            let hit_s = start_s + (dt * t);
+            let local_hit = local_prev + ((local_end - local_prev) * t);
+            let intersection = surface.to_world(local_hit, hit_s);
+            let normal = world_direction(surface, local_normal, hit_s);
+            let surface_velocity = surface.velocity_at(intersection, hit_s);
+            if (projectile.velocity - surface_velocity).dot(normal) >= 0.0 {
+                continue;
+            }
+
+            if best_hit.is_none_or(|(best_t, ..)| t < best_t) {
+                best_hit = Some((
+                    t,
+                    local_hit,
+                    local_normal,
+                    normal,
+                    surface_velocity,
+                    surface,
+                ));
+            }
+        }
+    }
+
+    if let Some((t, local_hit, local_normal, normal, surface_velocity, surface)) = best_hit {
+        projectile.velocity -= surface_velocity;
+        apply_bounce_impulse(projectile, normal, surface);
+        // Leave the ball just off the surface where it is at the end of the step, then
+        // carry it away from it for the rest of the step.
+        let rebound_s = dt * (1.0 - t);
+        projectile.position = surface.to_world(local_hit + (local_normal * 0.05), end_s)
+            + (projectile.velocity * rebound_s);
+        projectile.velocity += surface_velocity;
+        projectile.bounces += 1;
+        return Some((t, surface.to_world(local_hit, start_s + (dt * t))));
+    }
+
+    // No clean edge crossing, but the step ends inside a surface (one that moved over
+    // the ball, say): push the ball out through the nearest edge, and bounce it off
+    // that edge if the two are still closing.
+    let surface = surfaces
+        .iter()
+        .find(|surface| point_in_polygon(projectile.position, &surface.corners_at(end_s)))?;
+    let local = surface.to_local(projectile.position, end_s);
+    let (local_contact, local_normal) = bounce_surface_edges(&surface.corners)
+        .into_iter()
+        .filter_map(|(a, b)| {
+            let normal = outward_edge_normal(surface, a, b)?;
+            let t = ((local - a).dot(b - a) / (b - a).length_squared()).clamp(0.0, 1.0);
+            Some((a + ((b - a) * t), normal))
+        })
+        .min_by(|(p, _), (q, _)| local.distance(*p).total_cmp(&local.distance(*q)))?;
+    let contact = surface.to_world(local_contact, end_s);
+    let normal = world_direction(surface, local_normal, end_s);
+    let surface_velocity = surface.velocity_at(contact, end_s);
+    projectile.position = surface.to_world(local_contact + (local_normal * 0.05), end_s);
+    if (projectile.velocity - surface_velocity).dot(normal) < 0.0 {
+        projectile.velocity -= surface_velocity;
+        apply_bounce_impulse(projectile, normal, surface);
+        projectile.velocity += surface_velocity;
+        projectile.bounces += 1;
+    }
+    None
+}
+
+/// Outward unit normal of the edge `a`-`b` in `surface`'s rest pose, or `None` for
+/// a degenerate edge.
+fn outward_edge_normal(surface: &BounceSurface, a: Vec2, b: Vec2) -> Option<Vec2> {
+    let normal = (b - a).perp().normalize_or_zero();
+    if normal.length_squared() < 1e-8 {
+        return None;
+    }
+    if normal.dot(((a + b) * 0.5) - surface.pivot()) < 0.0 {
+        Some(-normal)
+    } else {
+        Some(normal)
+    }
+}
+
+/// A rest-pose direction turned with `surface` as it is at `time_s`.
+fn world_direction(surface: &BounceSurface, local: Vec2, time_s: f32) -> Vec2 {
+    (surface.to_world(local, time_s) - surface.to_world(Vec2::ZERO, time_s)).normalize_or_zero()
+}
+
+pub fn simulate_prediction(
+    config: LaunchConfig,
+    level: &Level,
+    integrator: Integrator,
+) -> Prediction {
+    simulate_shot(config, level, integrator, PREDICTION_MAX_STEPS)
+}
+
+/// Steps a shot at `FIXED_STEP_S` until it resolves, `max_steps` run out or
+/// `MAX_SIM_TIME_S` passes, recording every position.
+pub fn simulate_shot(
+    config: LaunchConfig,
+    level: &Level,
+    integrator: Integrator,
+    max_steps: usize,
+) -> Prediction {
+    let mut projectile = launch_projectile(config);
+    let mut points = vec![projectile.position];
+    let mut outcome = StepOutcome::Flying;
+
+    for _ in 0..max_steps {
+        outcome = step_projectile(&mut projectile, level, FIXED_STEP_S, integrator);
+        points.push(projectile.position);
+        if outcome != StepOutcome::Flying || projectile.elapsed_s > MAX_SIM_TIME_S {
+            break;
+        }
+    }
+
+    Prediction {
+        points,
+        range_m: projectile.position.x.max(0.0),
+        flight_time_s: projectile.elapsed_s,
+        bounces: projectile.bounces,
+        outcome,
+    }
+}
@@ -0,0 +1,430 @@
+//! Level solver: searches angle x speed x height for launches that win a level.
+//! A coarse grid finds candidates, then a pattern search refines the closest ones.
+
+use std::fmt;
+
+use glam::Vec2;
+
+use crate::core::integrate::Integrator;
+use crate::core::sim::{
+    FIXED_STEP_S, LaunchConfig, Level, MAX_SIM_TIME_S, SHOT_MAX_STEPS, StepOutcome,
+    distance_to_segment, launch_projectile, step_projectile,
+};
+
+/// Search space and effort. Defaults cover the game's slider ranges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolverSettings {
+    pub angle_range_deg: (f32, f32),
+    pub speed_range_mps: (f32, f32),
+    pub height_range_m: (f32, f32),
+    pub angle_steps: usize,
+    pub speed_steps: usize,
+    pub height_steps: usize,
+    /// How many of the closest grid samples get refined.
+    pub refine_candidates: usize,
+    /// Pattern-search moves per refined candidate.
+    pub refine_iterations: usize,
+    pub integrator: Integrator,
+    /// Step cap per trial flight; defaults to the live game's full shot budget.
+    pub max_steps: usize,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        Self {
+            angle_range_deg: (-89.0, 89.0),
+            speed_range_mps: (5.0, 500.0),
+            height_range_m: (0.0, 400.0),
+            angle_steps: 37,
+            speed_steps: 25,
+            height_steps: 6,
+            refine_candidates: 8,
+            refine_iterations: 40,
+            integrator: Integrator::SemiImplicitEuler,
+            max_steps: SHOT_MAX_STEPS,
+        }
+    }
+}
+
+/// A winning launch and how its predicted flight went.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Solution {
+    pub config: LaunchConfig,
+    pub flight_time_s: f32,
+    pub bounces: u32,
+    /// How far the path passes from the target centre, minus its radius; the more
+    /// negative, the more central the hit.
+    pub miss_m: f32,
+}
+
+/// No searched launch won the level; carries the nearest miss for tuning.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unsolvable {
+    pub closest: LaunchConfig,
+    pub miss_m: f32,
+}
+
+impl fmt::Display for Unsolvable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsolvable: closest miss {:.2} m at angle {:.2} deg, speed {:.2} m/s, height {:.2} m",
+            self.miss_m, self.closest.angle_deg, self.closest.speed_mps, self.closest.height_m
+        )
+    }
+}
+
+impl std::error::Error for Unsolvable {}
+
+#[derive(Clone, Copy, Debug)]
+struct Trial {
+    config: LaunchConfig,
+    outcome: StepOutcome,
+    flight_time_s: f32,
+    bounces: u32,
+    miss_m: f32,
+}
+
+/// Runs the `simulate_prediction` stepping, tracking how close the path passes to the
+/// target once it has made the required bounces. On a hit, the entering step is
+/// extended into a line, so a shot aimed through the centre scores best.
+fn evaluate(config: LaunchConfig, level: &Level, settings: &SolverSettings) -> Trial {
+    let mut projectile = launch_projectile(config);
+    let mut outcome = StepOutcome::Flying;
+    let mut closest_m = f32::INFINITY;
+
+    for _ in 0..settings.max_steps {
+        // Measure in the target's frame, so a moving target is scored where it is
+        // when the shot gets there.
+        let prev = projectile.position - level.target.position_at(projectile.elapsed_s);
+        outcome = step_projectile(&mut projectile, level, FIXED_STEP_S, settings.integrator);
+        let now = projectile.position - level.target.position_at(projectile.elapsed_s);
+        if outcome == StepOutcome::HitTarget {
+            closest_m = distance_to_segment(Vec2::ZERO, prev, now, true);
+        } else if projectile.bounces >= level.required_bounces {
+            let passed_m = distance_to_segment(Vec2::ZERO, prev, now, false);
+            closest_m = closest_m.min(passed_m);
+        }
+        if outcome != StepOutcome::Flying || projectile.elapsed_s > MAX_SIM_TIME_S {
+            break;
+        }
+    }
+
+    Trial {
+        config,
+        outcome,
+        flight_time_s: projectile.elapsed_s,
+        bounces: projectile.bounces,
+        miss_m: closest_m - level.target.radius_m,
+    }
+}
+
+fn grid_values(range: (f32, f32), steps: usize) -> Vec<f32> {
+    if steps <= 1 {
+        return vec![(range.0 + range.1) * 0.5];
+    }
+    let span = range.1 - range.0;
+    (0..steps)
+        .map(|i| range.0 + span * (i as f32 / (steps - 1) as f32))
+        .collect()
+}
+
+fn grid_spacing(range: (f32, f32), steps: usize) -> f32 {
+    (range.1 - range.0) / steps.saturating_sub(1).max(1) as f32
+}
+
+fn clamp_config(config: LaunchConfig, settings: &SolverSettings) -> LaunchConfig {
+    LaunchConfig {
+        angle_deg: config
+            .angle_deg
+            .clamp(settings.angle_range_deg.0, settings.angle_range_deg.1),
+        speed_mps: config
+            .speed_mps
+            .clamp(settings.speed_range_mps.0, settings.speed_range_mps.1),
+        height_m: config
+            .height_m
+            .clamp(settings.height_range_m.0, settings.height_range_m.1),
+    }
+}
+
+/// Each refinement iteration tries one step down and up along every axis
+/// (0 angle, 1 speed, 2 height), in this order.
+const COMPASS_MOVES: [(usize, f32); 6] = [
+    (0, -1.0),
+    (0, 1.0),
+    (1, -1.0),
+    (1, 1.0),
+    (2, -1.0),
+    (2, 1.0),
+];
+
+/// Compass search from a grid candidate, moving toward a smaller miss and halving
+/// the step whenever no neighbour improves. Kept as state so it can be resumed.
+#[derive(Clone, Copy, Debug)]
+struct Refinement {
+    best: Trial,
+    step: [f32; 3],
+    iteration: usize,
+    /// Index into `COMPASS_MOVES` of the move this iteration tries next.
+    next_move: usize,
+    improved: bool,
+}
+
+impl Refinement {
+    fn new(start: Trial, settings: &SolverSettings) -> Self {
+        Self {
+            best: start,
+            step: [
+                grid_spacing(settings.angle_range_deg, settings.angle_steps) * 0.5,
+                grid_spacing(settings.speed_range_mps, settings.speed_steps) * 0.5,
+                grid_spacing(settings.height_range_m, settings.height_steps) * 0.5,
+            ],
+            iteration: 0,
+            next_move: 0,
+            improved: false,
+        }
+    }
+
+    /// Flies the next neighbouring launch, or returns `None` once the iterations
+    /// are used up.
+    fn next_trial(&mut self, level: &Level, settings: &SolverSettings) -> Option<Trial> {
+        while self.iteration < settings.refine_iterations {
+            if self.next_move == COMPASS_MOVES.len() {
+                if !self.improved {
+                    self.step.iter_mut().for_each(|s| *s *= 0.5);
+                }
+                self.iteration += 1;
+                self.next_move = 0;
+                self.improved = false;
+                continue;
+            }
+            let (axis, sign) = COMPASS_MOVES[self.next_move];
+            self.next_move += 1;
+
+            let mut config = self.best.config;
+            match axis {
+                0 => config.angle_deg += sign * self.step[0],
+                1 => config.speed_mps += sign * self.step[1],
+                _ => config.height_m += sign * self.step[2],
+            }
+            let config = clamp_config(config, settings);
+            if config == self.best.config {
+                continue;
+            }
+            let trial = evaluate(config, level, settings);
+            if trial.miss_m < self.best.miss_m {
+                self.best = trial;
+                self.improved = true;
+            }
+            return Some(trial);
+        }
+        None
+    }
+}
+
+/// A `solve_level` run that can be advanced a few trial flights at a time, so the
+/// game can spread a search over frames instead of stalling on one.
+#[derive(Clone, Debug)]
+pub struct LevelSearch {
+    level: Level,
+    settings: SolverSettings,
+    grid: Vec<LaunchConfig>,
+    /// Grid trials so far; sorted closest-first once the grid is complete.
+    trials: Vec<Trial>,
+    wins: Vec<Trial>,
+    /// How many of the closest grid trials have started refining.
+    refined: usize,
+    refinement: Option<Refinement>,
+}
+
+impl LevelSearch {
+    pub fn new(level: &Level, settings: SolverSettings) -> Self {
+        let mut grid = Vec::new();
+        for angle_deg in grid_values(settings.angle_range_deg, settings.angle_steps) {
+            for speed_mps in grid_values(settings.speed_range_mps, settings.speed_steps) {
+                for height_m in grid_values(settings.height_range_m, settings.height_steps) {
+                    grid.push(LaunchConfig {
+                        angle_deg,
+                        speed_mps,
+                        height_m,
+                    });
+                }
+            }
+        }
+        Self {
+            level: level.clone(),
+            settings,
+            trials: Vec::with_capacity(grid.len()),
+            grid,
+            wins: Vec::new(),
+            refined: 0,
+            refinement: None,
+        }
+    }
+
+    /// Flies up to `evaluations` more trial launches. Returns the outcome once the
+    /// grid and every refinement are done, `None` while work remains.
+    pub fn advance(&mut self, evaluations: usize) -> Option<Result<Vec<Solution>, Unsolvable>> {
+        let mut budget = evaluations;
+        loop {
+            if self.trials.len() < self.grid.len() {
+                if budget == 0 {
+                    return None;
+                }
+                budget -= 1;
+                let config = self.grid[self.trials.len()];
+                self.trials
+                    .push(evaluate(config, &self.level, &self.settings));
+                if self.trials.len() == self.grid.len() {
+                    self.trials.sort_by(|a, b| a.miss_m.total_cmp(&b.miss_m));
+                    self.wins.extend(
+                        self.trials
+                            .iter()
+                            .filter(|trial| trial.outcome == StepOutcome::HitTarget),
+                    );
+                }
+            } else if let Some(refinement) = &mut self.refinement {
+                if budget == 0 {
+                    return None;
+                }
+                match refinement.next_trial(&self.level, &self.settings) {
+                    Some(trial) => {
+                        budget -= 1;
+                        if trial.outcome == StepOutcome::HitTarget {
+                            self.wins.push(trial);
+                        }
+                    }
+                    None => self.refinement = None,
+                }
+            } else if self.refined < self.settings.refine_candidates.min(self.trials.len()) {
+                self.refinement = Some(Refinement::new(self.trials[self.refined], &self.settings));
+                self.refined += 1;
+            } else {
+                return Some(self.result());
+            }
+        }
+    }
+
+    fn result(&self) -> Result<Vec<Solution>, Unsolvable> {
+        if self.wins.is_empty() {
+            let closest = self
+                .trials
+                .first()
+                .map_or(self.level.default_launch, |t| t.config);
+            let miss_m = self.trials.first().map_or(f32::INFINITY, |t| t.miss_m);
+            return Err(Unsolvable { closest, miss_m });
+        }
+
+        let mut wins = self.wins.clone();
+        wins.sort_by(|a, b| a.miss_m.total_cmp(&b.miss_m));
+        wins.dedup_by(|a, b| a.config == b.config);
+        Ok(wins
+            .into_iter()
+            .map(|trial| Solution {
+                config: trial.config,
+                flight_time_s: trial.flight_time_s,
+                bounces: trial.bounces,
+                miss_m: trial.miss_m,
+            })
+            .collect())
+    }
+}
+
+/// Searches for launches whose prediction ends in `StepOutcome::HitTarget` with the
+/// level's required bounces. Solutions are sorted most-central first, so the head
+/// of the list is the one most tolerant of small aiming errors.
+pub fn solve_level(level: &Level, settings: &SolverSettings) -> Result<Vec<Solution>, Unsolvable> {
+    LevelSearch::new(level, *settings)
+        .advance(usize::MAX)
+        .expect("an unbounded search runs to completion")
+}
+
+impl SolverSettings {
+    /// A small search window around `config` (within the slider ranges), cheap
+    /// enough to run from a button press.
+    pub fn around(config: LaunchConfig, integrator: Integrator) -> Self {
+        let full = Self::default();
+        let window = |value: f32, half: f32, range: (f32, f32)| {
+            ((value - half).max(range.0), (value + half).min(range.1))
+        };
+        Self {
+            angle_range_deg: window(config.angle_deg, 20.0, full.angle_range_deg),
+            speed_range_mps: window(
+                config.speed_mps,
+                (config.speed_mps * 0.4).max(20.0),
+                full.speed_range_mps,
+            ),
+            height_range_m: window(config.height_m, 40.0, full.height_range_m),
+            angle_steps: 9,
+            speed_steps: 9,
+            height_steps: 3,
+            refine_candidates: 3,
+            refine_iterations: 12,
+            integrator,
+            max_steps: full.max_steps,
+        }
+    }
+}
+
+/// `nearest_solution` as a resumable search: the window around the aim first, then
+/// the full slider ranges only if nothing nearby wins.
+#[derive(Clone, Debug)]
+pub struct NearestSearch {
+    config: LaunchConfig,
+    search: LevelSearch,
+    widened: bool,
+}
+
+impl NearestSearch {
+    pub fn new(level: &Level, config: LaunchConfig, integrator: Integrator) -> Self {
+        Self {
+            config,
+            search: LevelSearch::new(level, SolverSettings::around(config, integrator)),
+            widened: false,
+        }
+    }
+
+    /// Flies up to `evaluations` more trial launches; `None` while work remains.
+    pub fn advance(&mut self, evaluations: usize) -> Option<Result<Solution, Unsolvable>> {
+        let solutions = match self.search.advance(evaluations)? {
+            Ok(solutions) => solutions,
+            Err(err) if self.widened => return Some(Err(err)),
+            Err(_) => {
+                let full = SolverSettings {
+                    integrator: self.search.settings.integrator,
+                    ..SolverSettings::default()
+                };
+                self.search = LevelSearch::new(&self.search.level, full);
+                self.widened = true;
+                return None;
+            }
+        };
+        let config = self.config;
+        let distance = |candidate: &Solution| {
+            let angle = (candidate.config.angle_deg - config.angle_deg) / 10.0;
+            let speed = (candidate.config.speed_mps - config.speed_mps)
+                / (config.speed_mps.max(10.0) * 0.1);
+            let height = (candidate.config.height_m - config.height_m) / 20.0;
+            (angle * angle) + (speed * speed) + (height * height)
+        };
+        Some(Ok(solutions
+            .into_iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .expect("solve_level never returns an empty Ok")))
+    }
+}
+
+/// Finds the winning launch closest to `config`: searches near it first and only
+/// falls back to the full slider ranges when nothing nearby wins.
+pub fn nearest_solution(
+    level: &Level,
+    config: LaunchConfig,
+    integrator: Integrator,
+) -> Result<Solution, Unsolvable> {
+    let mut search = NearestSearch::new(level, config, integrator);
+    loop {
+        if let Some(result) = search.advance(usize::MAX) {
+            return result;
+        }
+    }
+}
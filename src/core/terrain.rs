@@ -0,0 +1,199 @@
+use crate::core::ballistics::{
+    BallisticsError, FlightSummary, LaunchInputs, ensure_finite, validate_mass,
+};
+use crate::core::drag::{
+    Atmosphere, DragModel, LANDING_BISECTION_STEPS, MAX_FLIGHT_TIME_S, QUADRATIC_STEP_S,
+    acceleration, state_at_time_with_drag, validate,
+};
+use crate::core::integrate::{FlightState, Integrator};
+
+/// Marching step used to find the first ground crossing before bisecting it.
+const TERRAIN_STEP_S: f64 = QUADRATIC_STEP_S;
+
+/// Ground height as a function of horizontal position.
+/// Any `Fn(f64) -> f64` works as a height function.
+pub trait Ground {
+    fn height_at(&self, x_m: f64) -> f64;
+
+    fn validate(&self) -> Result<(), BallisticsError> {
+        Ok(())
+    }
+}
+
+impl<F: Fn(f64) -> f64> Ground for F {
+    fn height_at(&self, x_m: f64) -> f64 {
+        self(x_m)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GroundProfile {
+    Flat {
+        height_m: f64,
+    },
+    /// Straight slope through (0, `height_m`); positive angles rise downrange.
+    Incline {
+        height_m: f64,
+        slope_deg: f64,
+    },
+    /// Piecewise-linear ground through points sorted by x, held flat past either end.
+    /// An empty polyline is flat ground at y = 0.
+    Polyline(Vec<(f64, f64)>),
+}
+
+impl Default for GroundProfile {
+    fn default() -> Self {
+        GroundProfile::Flat { height_m: 0.0 }
+    }
+}
+
+impl Ground for GroundProfile {
+    fn height_at(&self, x_m: f64) -> f64 {
+        match self {
+            GroundProfile::Flat { height_m } => *height_m,
+            GroundProfile::Incline {
+                height_m,
+                slope_deg,
+            } => height_m + (x_m * slope_deg.to_radians().tan()),
+            GroundProfile::Polyline(points) => polyline_height(points, x_m),
+        }
+    }
+
+    fn validate(&self) -> Result<(), BallisticsError> {
+        match self {
+            GroundProfile::Flat { height_m } => ensure_finite(&[("ground_height_m", *height_m)])?,
+            GroundProfile::Incline {
+                height_m,
+                slope_deg,
+            } => {
+                ensure_finite(&[("ground_height_m", *height_m), ("slope_deg", *slope_deg)])?;
+                // A vertical (or steeper) incline has an infinite height function.
+                if slope_deg.abs() >= 90.0 {
+                    return Err(BallisticsError::NonFiniteInput {
+                        field: "slope_deg",
+                        value: *slope_deg,
+                    });
+                }
+            }
+            GroundProfile::Polyline(points) => {
+                for &(x_m, y_m) in points {
+                    ensure_finite(&[("terrain_x_m", x_m), ("terrain_y_m", y_m)])?;
+                }
+            }
+        }
+        if let GroundProfile::Polyline(points) = self
+            && let Some(pair) = points.windows(2).find(|pair| pair[1].0 < pair[0].0)
+        {
+            return Err(BallisticsError::UnsortedTerrain { x_m: pair[1].0 });
+        }
+        Ok(())
+    }
+}
+
+fn polyline_height(points: &[(f64, f64)], x_m: f64) -> f64 {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return 0.0;
+    };
+    if x_m <= first.0 {
+        return first.1;
+    }
+    if x_m >= last.0 {
+        return last.1;
+    }
+    let i = points.partition_point(|p| p.0 <= x_m).max(1);
+    let (x0, y0) = points[i - 1];
+    let (x1, y1) = points[i];
+    if x1 == x0 {
+        return y1;
+    }
+    y0 + ((y1 - y0) * (x_m - x0) / (x1 - x0))
+}
+
+/// Where and when a flight first meets the ground, with the velocity it arrives at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroundLanding {
+    pub time_s: f64,
+    pub x_m: f64,
+    pub y_m: f64,
+    pub vx_mps: f64,
+    pub vy_mps: f64,
+}
+
+/// First intersection of the flight with `ground`, under the given drag and wind.
+/// The ground replaces `landing_height_m`, which is ignored here.
+/// Vacuum and linear drag use their closed forms; quadratic drag is stepped with RK4.
+pub fn landing_on_ground<G: Ground + ?Sized>(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    ground: &G,
+) -> Result<GroundLanding, BallisticsError> {
+    validate(inputs, atmosphere)?;
+    ground.validate()?;
+
+    let g = inputs.gravity_mps2;
+    let advance = |state: FlightState, time_s: f64, dt: f64| match atmosphere.drag {
+        DragModel::Quadratic { .. } => {
+            Integrator::Rk4.advance(state, dt, |s| acceleration(s, g, atmosphere))
+        }
+        _ => state_at_time_with_drag(inputs, atmosphere, time_s + dt),
+    };
+    let clearance = |state: &FlightState| state.y_m - ground.height_at(state.x_m);
+
+    let mut state = FlightState::launch(inputs);
+    let launch_clearance = clearance(&state);
+    if launch_clearance < 0.0 {
+        return Err(BallisticsError::LaunchBelowGround {
+            clearance_m: launch_clearance,
+        });
+    }
+
+    let mut time_s = 0.0;
+    while time_s < MAX_FLIGHT_TIME_S {
+        let next = advance(state, time_s, TERRAIN_STEP_S);
+        if clearance(&next) <= 0.0 {
+            // Bisect inside the step; a launch sitting on the ground counts as airborne.
+            let (mut lo, mut hi) = (0.0, TERRAIN_STEP_S);
+            for _ in 0..LANDING_BISECTION_STEPS {
+                let mid = 0.5 * (lo + hi);
+                if clearance(&advance(state, time_s, mid)) > 0.0 {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            let t_step = 0.5 * (lo + hi);
+            let landed = advance(state, time_s, t_step);
+            return Ok(GroundLanding {
+                time_s: time_s + t_step,
+                x_m: landed.x_m,
+                y_m: landed.y_m,
+                vx_mps: landed.vx_mps,
+                vy_mps: landed.vy_mps,
+            });
+        }
+        state = next;
+        time_s += TERRAIN_STEP_S;
+    }
+
+    Err(BallisticsError::FlightTimeout {
+        max_time_s: MAX_FLIGHT_TIME_S,
+    })
+}
+
+/// Terrain counterpart of `flight_summary_with_drag`: the touchdown is the first ground contact.
+pub fn flight_summary_on_ground<G: Ground + ?Sized>(
+    inputs: LaunchInputs,
+    atmosphere: Atmosphere,
+    ground: &G,
+    mass_kg: Option<f64>,
+) -> Result<FlightSummary, BallisticsError> {
+    validate_mass(mass_kg)?;
+    let landing = landing_on_ground(inputs, atmosphere, ground)?;
+    Ok(FlightSummary::at_impact(
+        landing.time_s,
+        landing.x_m - inputs.launch_x_m,
+        landing.vx_mps,
+        landing.vy_mps,
+        mass_kg,
+    ))
+}
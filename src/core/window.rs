@@ -1,40 +1,54 @@
+use crate::core::float::Real;
+
 pub const DISTANCE_TO_HEIGHT_RATIO: f64 = 2.0; // x:y data window ratio
 
 const X_PADDING_RATIO: f64 = 0.06;
 const Y_PADDING_RATIO: f64 = 0.10;
-
-pub fn fixed_ratio_axis_window_f64(raw_max_x: f64, raw_max_y: f64) -> (f64, f64) {
-    let raw_x_span = raw_max_x.max(1.0);
-    let raw_y_span = raw_max_y.max(1.0);
-    let x_pad = raw_x_span * X_PADDING_RATIO;
-    let y_pad = raw_y_span * Y_PADDING_RATIO;
-
-    let mut x_span = (raw_max_x + x_pad).max(1.0);
-    let mut y_span = (raw_max_y + y_pad).max(1.0);
-
-    if x_span / y_span < DISTANCE_TO_HEIGHT_RATIO {
-        x_span = y_span * DISTANCE_TO_HEIGHT_RATIO;
+// Samples this close to an axis (e.g. the landing point's rounding error) count as on it.
+const AXIS_TOLERANCE: f64 = 1e-6;
+
+pub fn fixed_ratio_axis_window<T: Real>(raw_max_x: T, raw_max_y: T) -> (T, T) {
+    let one = T::one();
+    let ratio = T::from_f64(DISTANCE_TO_HEIGHT_RATIO);
+    let raw_x_span = raw_max_x.max(one);
+    let raw_y_span = raw_max_y.max(one);
+    let x_pad = raw_x_span * T::from_f64(X_PADDING_RATIO);
+    let y_pad = raw_y_span * T::from_f64(Y_PADDING_RATIO);
+
+    let mut x_span = (raw_max_x + x_pad).max(one);
+    let mut y_span = (raw_max_y + y_pad).max(one);
+
+    if x_span / y_span < ratio {
+        x_span = y_span * ratio;
     } else {
-        y_span = x_span / DISTANCE_TO_HEIGHT_RATIO;
+        y_span = x_span / ratio;
     }
 
     (x_span, y_span)
 }
 
-pub fn fixed_ratio_axis_window_f32(raw_max_x: f32, raw_max_y: f32) -> (f32, f32) {
-    let raw_x_span = raw_max_x.max(1.0);
-    let raw_y_span = raw_max_y.max(1.0);
-    let x_pad = raw_x_span * X_PADDING_RATIO as f32;
-    let y_pad = raw_y_span * Y_PADDING_RATIO as f32;
-
-    let mut x_span = (raw_max_x + x_pad).max(1.0);
-    let mut y_span = (raw_max_y + y_pad).max(1.0);
-
-    if x_span / y_span < DISTANCE_TO_HEIGHT_RATIO as f32 {
-        x_span = y_span * DISTANCE_TO_HEIGHT_RATIO as f32;
+/// Axis bounds covering [min, max] in both directions while keeping the fixed x:y ratio.
+/// The origin and the y = 0 ground line stay in view; the window only extends left or below
+/// them (with padding) when data sits there, e.g. a launch offset or downhill landing.
+pub fn fixed_ratio_axis_bounds<T: Real>(
+    raw_min_x: T,
+    raw_max_x: T,
+    raw_min_y: T,
+    raw_max_y: T,
+) -> ((T, T), (T, T)) {
+    let zero = T::zero();
+    let tolerance = T::from_f64(AXIS_TOLERANCE);
+    let x_min = if raw_min_x < -tolerance {
+        raw_min_x - ((raw_max_x - raw_min_x).max(T::one()) * T::from_f64(X_PADDING_RATIO))
     } else {
-        y_span = x_span / DISTANCE_TO_HEIGHT_RATIO as f32;
-    }
-
-    (x_span, y_span)
+        zero
+    };
+    let y_min = if raw_min_y < -tolerance {
+        raw_min_y - ((raw_max_y - raw_min_y).max(T::one()) * T::from_f64(Y_PADDING_RATIO))
+    } else {
+        zero
+    };
+    let (x_span, y_span) =
+        fixed_ratio_axis_window(raw_max_x.max(zero) - x_min, raw_max_y.max(zero) - y_min);
+    ((x_min, x_min + x_span), (y_min, y_min + y_span))
 }
@@ -1,8 +1,23 @@
 use chrono::{Datelike, Local};
-use parabolic_rust::core::ballistics::{LaunchInputs, flight_time_and_range, sample_trajectory};
-use parabolic_rust::core::window::fixed_ratio_axis_window_f64;
+use parabolic_rust::core::ballistics::{
+    Apex, BallisticsError, Body, EARTH_GRAVITY_MPS2, LaunchInputs, flight_time_and_range,
+    launch_angles_to_target, minimum_speed_to_target, optimal_launch_angle, sample_safety_envelope,
+    sample_trajectory,
+};
+use parabolic_rust::core::campaign::earth_wind_samples;
+use parabolic_rust::core::drag::{
+    Atmosphere, DragModel, apex_with_drag, flight_summary_integrated, flight_summary_with_drag,
+    optimal_launch_angle_with_drag, sample_trajectory_integrated, sample_trajectory_with_drag,
+};
+use parabolic_rust::core::integrate::Integrator;
+use parabolic_rust::core::sim::{LaunchConfig, Level, Prediction, SHOT_MAX_STEPS, simulate_shot};
+use parabolic_rust::core::solver::{SolverSettings, solve_level};
+use parabolic_rust::core::terrain::{Ground, GroundProfile, flight_summary_on_ground};
+use parabolic_rust::core::window::fixed_ratio_axis_bounds;
 use plotters::prelude::*;
+use serde::Serialize;
 use std::env;
+use std::fmt;
 use std::io::{self, Write};
 
 const PLOT_WIDTH: u32 = 1500;
@@ -12,15 +27,215 @@ const X_LABEL_AREA: u32 = 60;
 const Y_LABEL_AREA: u32 = 100;
 const TRAJECTORY_SAMPLES: usize = 500;
 const TRAJECTORY_LINE_WIDTH: u32 = 3;
+const DEFAULT_INTEGRATION_STEP_S: f64 = 1.0 / 240.0;
+const ENVELOPE_FAN_STEP_DEG: f64 = 7.5;
+const TERRAIN_SAMPLES: usize = 400;
+const SOLVER_WINDS_PER_DIRECTION: usize = 3;
 
 type Inputs = LaunchInputs;
 
+#[derive(Clone, Debug)]
+struct CliOptions {
+    gravity_mps2: f64,
+    atmosphere: Atmosphere,
+    integrator: Option<Integrator>,
+    step_s: f64,
+    mass_kg: Option<f64>,
+    ground: Option<GroundProfile>,
+    launch_x_m: f64,
+    landing_height_m: f64,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        Self {
+            gravity_mps2: EARTH_GRAVITY_MPS2,
+            atmosphere: Atmosphere::VACUUM,
+            integrator: None,
+            step_s: DEFAULT_INTEGRATION_STEP_S,
+            mass_kg: None,
+            ground: None,
+            launch_x_m: 0.0,
+            landing_height_m: 0.0,
+        }
+    }
+}
+
+/// Everything `run()` can fail with; each case maps to its own process exit code.
+#[derive(Debug)]
+enum CliError {
+    Input(String),
+    Plot(String),
+    Unsolvable(String),
+    Ballistics(BallisticsError),
+}
+
+impl CliError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Input(_) => 2,
+            CliError::Plot(_) => 3,
+            CliError::Unsolvable(_) => 4,
+            CliError::Ballistics(err) => match err {
+                BallisticsError::NonFiniteInput { .. } => 10,
+                BallisticsError::NegativeSpeed { .. } => 11,
+                BallisticsError::NonPositiveGravity { .. } => 12,
+                BallisticsError::NegativeDrag { .. } => 13,
+                BallisticsError::NonPositiveMass { .. } => 14,
+                BallisticsError::NonPositiveStep { .. } => 15,
+                BallisticsError::UnsortedTerrain { .. } => 16,
+                BallisticsError::NoRealLanding { .. } => 20,
+                BallisticsError::NegativeLandingTime { .. } => 21,
+                BallisticsError::PeaksBelowGround { .. } => 22,
+                BallisticsError::FlightTimeout { .. } => 23,
+                BallisticsError::LaunchBelowGround { .. } => 24,
+                BallisticsError::TargetNotDownrange { .. } => 30,
+                BallisticsError::OutOfReach { .. } => 31,
+            },
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Input(msg) | CliError::Plot(msg) | CliError::Unsolvable(msg) => {
+                write!(f, "{msg}")
+            }
+            CliError::Ballistics(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<String> for CliError {
+    fn from(msg: String) -> Self {
+        CliError::Input(msg)
+    }
+}
+
+impl From<BallisticsError> for CliError {
+    fn from(err: BallisticsError) -> Self {
+        CliError::Ballistics(err)
+    }
+}
+
 fn parse_f64(value: &str, label: &str) -> Result<f64, String> {
     value
         .parse::<f64>()
         .map_err(|_| format!("Invalid {label}: '{value}'. Expected a number."))
 }
 
+fn option_value<'a>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> Result<&'a str, String> {
+    iter.next()
+        .map(String::as_str)
+        .ok_or_else(|| format!("Missing value for {flag}."))
+}
+
+fn parse_body(value: &str) -> Result<Body, String> {
+    Body::from_name(value).ok_or_else(|| {
+        let known: Vec<&str> = Body::ALL.iter().map(|body| body.name()).collect();
+        format!(
+            "Unknown body: '{value}'. Expected one of: {}.",
+            known.join(", ")
+        )
+    })
+}
+
+fn parse_integrator(value: &str) -> Result<Integrator, String> {
+    Integrator::from_name(value).ok_or_else(|| {
+        let known: Vec<&str> = Integrator::ALL.iter().map(|i| i.name()).collect();
+        format!(
+            "Unknown integrator: '{value}'. Expected one of: {}.",
+            known.join(", ")
+        )
+    })
+}
+
+/// Parses `x:y,x:y,...` into polyline terrain points.
+fn parse_terrain(value: &str) -> Result<GroundProfile, String> {
+    let points = value
+        .split(',')
+        .map(|pair| {
+            let (x, y) = pair
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid terrain point: '{pair}'. Expected x:y."))?;
+            Ok((
+                parse_f64(x.trim(), "terrain x")?,
+                parse_f64(y.trim(), "terrain y")?,
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(GroundProfile::Polyline(points))
+}
+
+fn split_options(args: &[String]) -> Result<(Vec<String>, CliOptions), String> {
+    let mut positional = Vec::new();
+    let mut options = CliOptions::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--body" => {
+                options.gravity_mps2 =
+                    parse_body(option_value(&mut iter, "--body")?)?.gravity_mps2();
+            }
+            "--gravity" => {
+                options.gravity_mps2 = parse_f64(option_value(&mut iter, "--gravity")?, "gravity")?;
+            }
+            "--drag-linear" => {
+                let k_per_s = parse_f64(option_value(&mut iter, "--drag-linear")?, "linear drag")?;
+                options.atmosphere.drag = DragModel::Linear { k_per_s };
+            }
+            "--drag-quadratic" => {
+                let k_per_m = parse_f64(
+                    option_value(&mut iter, "--drag-quadratic")?,
+                    "quadratic drag",
+                )?;
+                options.atmosphere.drag = DragModel::Quadratic { k_per_m };
+            }
+            "--wind" => {
+                options.atmosphere.wind_accel_x_mps2 =
+                    parse_f64(option_value(&mut iter, "--wind")?, "wind")?;
+            }
+            "--integrator" => {
+                options.integrator =
+                    Some(parse_integrator(option_value(&mut iter, "--integrator")?)?);
+            }
+            "--step" => {
+                options.step_s = parse_f64(option_value(&mut iter, "--step")?, "step")?;
+            }
+            "--mass" => {
+                options.mass_kg = Some(parse_f64(option_value(&mut iter, "--mass")?, "mass")?);
+            }
+            "--launch-x" => {
+                options.launch_x_m = parse_f64(option_value(&mut iter, "--launch-x")?, "launch x")?;
+            }
+            "--landing-height" => {
+                options.landing_height_m = parse_f64(
+                    option_value(&mut iter, "--landing-height")?,
+                    "landing height",
+                )?;
+            }
+            "--slope" => {
+                let slope_deg = parse_f64(option_value(&mut iter, "--slope")?, "slope")?;
+                options.ground = Some(GroundProfile::Incline {
+                    height_m: 0.0,
+                    slope_deg,
+                });
+            }
+            "--terrain" => {
+                options.ground = Some(parse_terrain(option_value(&mut iter, "--terrain")?)?);
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    Ok((positional, options))
+}
+
 fn read_f64(prompt: &str) -> Result<f64, String> {
     loop {
         print!("{prompt}");
@@ -44,56 +259,394 @@ fn read_f64(prompt: &str) -> Result<f64, String> {
     }
 }
 
-fn get_inputs_from_user() -> Result<Inputs, String> {
+fn get_inputs_from_user(options: &CliOptions) -> Result<Inputs, String> {
     Ok(Inputs {
         angle_deg: read_f64("Angle (degrees): ")?,
         speed_mps: read_f64("Velocity (m/s): ")?,
         height_m: read_f64("Height (m): ")?,
+        gravity_mps2: options.gravity_mps2,
+        launch_x_m: options.launch_x_m,
+        landing_height_m: options.landing_height_m,
     })
 }
 
-fn get_inputs_from_args(args: &[String]) -> Result<Inputs, String> {
-    if args.len() != 4 {
+fn get_inputs_from_args(args: &[String], options: &CliOptions) -> Result<Inputs, String> {
+    if args.len() != 3 {
         return Err(
             "Expected exactly 3 arguments: <angle_deg> <velocity_mps> <height_m>.".to_string(),
         );
     }
 
     Ok(Inputs {
-        angle_deg: parse_f64(&args[1], "angle")?,
-        speed_mps: parse_f64(&args[2], "velocity")?,
-        height_m: parse_f64(&args[3], "height")?,
+        angle_deg: parse_f64(&args[0], "angle")?,
+        speed_mps: parse_f64(&args[1], "velocity")?,
+        height_m: parse_f64(&args[2], "height")?,
+        gravity_mps2: options.gravity_mps2,
+        launch_x_m: options.launch_x_m,
+        landing_height_m: options.landing_height_m,
     })
 }
 
+fn run_solve(args: &[String], options: &CliOptions) -> Result<(), CliError> {
+    if args.len() != 4 {
+        return Err(CliError::Input(
+            "solve expects exactly 4 arguments: <velocity_mps> <height_m> <target_x_m> <target_y_m>."
+                .to_string(),
+        ));
+    }
+
+    let speed_mps = parse_f64(&args[0], "velocity")?;
+    let height_m = parse_f64(&args[1], "height")?;
+    let target_x_m = parse_f64(&args[2], "target x")?;
+    let target_y_m = parse_f64(&args[3], "target y")?;
+
+    let solutions = launch_angles_to_target(
+        speed_mps,
+        height_m,
+        target_x_m,
+        target_y_m,
+        options.gravity_mps2,
+    )?;
+
+    println!(
+        "\nLow angle: {:.4} deg (reaches target in {:.4} s)",
+        solutions.low_deg, solutions.low_time_s
+    );
+    println!(
+        "High angle: {:.4} deg (reaches target in {:.4} s)",
+        solutions.high_deg, solutions.high_time_s
+    );
+
+    Ok(())
+}
+
+fn run_min_speed(args: &[String], options: &CliOptions) -> Result<(), CliError> {
+    if args.len() != 3 {
+        return Err(CliError::Input(
+            "min-speed expects exactly 3 arguments: <height_m> <target_x_m> <target_y_m>."
+                .to_string(),
+        ));
+    }
+
+    let height_m = parse_f64(&args[0], "height")?;
+    let target_x_m = parse_f64(&args[1], "target x")?;
+    let target_y_m = parse_f64(&args[2], "target y")?;
+
+    let solution = minimum_speed_to_target(height_m, target_x_m, target_y_m, options.gravity_mps2)?;
+
+    println!("\nMinimum velocity: {:.4} m/s", solution.speed_mps);
+    println!("Required angle: {:.4} deg", solution.angle_deg);
+    println!("Time to target: {:.4} s", solution.time_s);
+
+    Ok(())
+}
+
+fn run_optimal(args: &[String], options: &CliOptions) -> Result<(), CliError> {
+    if args.len() != 2 {
+        return Err(CliError::Input(
+            "optimal expects exactly 2 arguments: <velocity_mps> <height_m>.".to_string(),
+        ));
+    }
+
+    let speed_mps = parse_f64(&args[0], "velocity")?;
+    let height_m = parse_f64(&args[1], "height")?;
+
+    // Range only depends on the drop to the landing elevation.
+    let optimal = optimal_launch_angle_with_drag(
+        speed_mps,
+        height_m - options.landing_height_m,
+        options.gravity_mps2,
+        options.atmosphere,
+    )?;
+
+    println!("\nOptimal angle: {:.4} deg", optimal.angle_deg);
+    println!("Maximum range: {:.4} m", optimal.range_m);
+
+    run_trajectory(
+        Inputs {
+            angle_deg: optimal.angle_deg,
+            speed_mps,
+            height_m,
+            gravity_mps2: options.gravity_mps2,
+            launch_x_m: options.launch_x_m,
+            landing_height_m: options.landing_height_m,
+        },
+        options,
+    )
+}
+
+fn run_envelope(args: &[String], options: &CliOptions) -> Result<(), CliError> {
+    if args.len() != 2 {
+        return Err(CliError::Input(
+            "envelope expects exactly 2 arguments: <velocity_mps> <height_m>.".to_string(),
+        ));
+    }
+    if !options.atmosphere.is_vacuum() {
+        return Err(CliError::Input(
+            "envelope is a vacuum result; drop --drag-linear/--drag-quadratic/--wind.".to_string(),
+        ));
+    }
+    if options.launch_x_m != 0.0 || options.landing_height_m != 0.0 {
+        return Err(CliError::Input(
+            "envelope is drawn from the origin down to y = 0; drop --launch-x/--landing-height."
+                .to_string(),
+        ));
+    }
+
+    let speed_mps = parse_f64(&args[0], "velocity")?;
+    let height_m = parse_f64(&args[1], "height")?;
+    let gravity_mps2 = options.gravity_mps2;
+
+    let envelope = sample_safety_envelope(speed_mps, height_m, gravity_mps2, TRAJECTORY_SAMPLES)?;
+    let reach = optimal_launch_angle(speed_mps, height_m, gravity_mps2)?;
+
+    let fan = envelope_fan(speed_mps, height_m, gravity_mps2);
+
+    println!(
+        "\nEnvelope peak: {:.4} m (straight up)",
+        envelope.first().map_or(height_m, |p| p.1)
+    );
+    println!(
+        "Envelope ground reach: {:.4} m (at {:.4} deg)",
+        reach.range_m, reach.angle_deg
+    );
+
+    let output_image = build_envelope_image_name(speed_mps, height_m, gravity_mps2);
+    save_envelope_plot(&envelope, &fan, &output_image).map_err(CliError::Plot)?;
+    println!("Saved plot: {output_image}");
+
+    Ok(())
+}
+
+/// Sample arcs drawn under the envelope, one per fan angle from 0 to 90 degrees.
+/// From below ground only the steeper angles climb back to y = 0; the rest are skipped.
+fn envelope_fan(speed_mps: f64, height_m: f64, gravity_mps2: f64) -> Vec<Vec<(f64, f64)>> {
+    let fan_count = (90.0 / ENVELOPE_FAN_STEP_DEG).round() as usize;
+    (0..=fan_count)
+        .filter_map(|i| {
+            let inputs = Inputs {
+                angle_deg: i as f64 * ENVELOPE_FAN_STEP_DEG,
+                speed_mps,
+                height_m,
+                gravity_mps2,
+                ..Inputs::default()
+            };
+            let (time, _) = flight_time_and_range(inputs).ok()?;
+            Some(sample_trajectory(inputs, time, TRAJECTORY_SAMPLES))
+        })
+        .collect()
+}
+
+/// What `simulate` prints, one JSON object per shot.
+#[derive(Serialize)]
+struct SimulationReport<'a> {
+    level: &'a str,
+    angle_deg: f32,
+    speed_mps: f32,
+    height_m: f32,
+    wind_mps2: f32,
+    outcome: &'static str,
+    bounces: u32,
+    flight_time_s: f32,
+    range_m: f32,
+    /// `[x, y]` pairs, rounded to 0.1 mm.
+    trajectory: Vec<[f32; 2]>,
+}
+
+/// Renders a headless shot as JSON; the trajectory is a list of `[x, y]` pairs.
+fn simulation_json(level: &Level, config: LaunchConfig, prediction: &Prediction) -> String {
+    let round = |value: f32| (value * 1e4).round() / 1e4;
+    let report = SimulationReport {
+        level: &level.code,
+        angle_deg: config.angle_deg,
+        speed_mps: config.speed_mps,
+        height_m: config.height_m,
+        wind_mps2: level.environment.wind_accel_x_mps2,
+        outcome: prediction.outcome.name(),
+        bounces: prediction.bounces,
+        flight_time_s: round(prediction.flight_time_s),
+        range_m: round(prediction.range_m),
+        trajectory: prediction
+            .points
+            .iter()
+            .map(|point| [round(point.x), round(point.y)])
+            .collect(),
+    };
+    serde_json::to_string(&report).expect("simulation reports always serialise")
+}
+
+/// Parses `--level` and the optional launch overrides that follow `simulate`.
+fn parse_simulate_args(args: &[String], levels: &[Level]) -> Result<(Level, LaunchConfig), String> {
+    let mut level = None;
+    let (mut angle_deg, mut speed_mps, mut height_m) = (None, None, None);
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--level" => {
+                let code = option_value(&mut iter, "--level")?;
+                let found = Level::find(levels, code).ok_or_else(|| {
+                    let known: Vec<&str> = levels.iter().map(|level| level.code.as_str()).collect();
+                    format!(
+                        "Unknown level: '{code}'. Expected one of: {}.",
+                        known.join(", ")
+                    )
+                })?;
+                level = Some(found.clone());
+            }
+            "--angle" => angle_deg = Some(parse_f64(option_value(&mut iter, "--angle")?, "angle")?),
+            "--speed" => speed_mps = Some(parse_f64(option_value(&mut iter, "--speed")?, "speed")?),
+            "--height" => {
+                height_m = Some(parse_f64(option_value(&mut iter, "--height")?, "height")?)
+            }
+            other => return Err(format!("Unexpected simulate argument: '{other}'.")),
+        }
+    }
+
+    let level = level.ok_or_else(|| "simulate needs --level <code>.".to_string())?;
+    let defaults = level.default_launch;
+    let config = LaunchConfig {
+        angle_deg: angle_deg.map_or(defaults.angle_deg, |v| v as f32),
+        speed_mps: speed_mps.map_or(defaults.speed_mps, |v| v as f32),
+        height_m: height_m.map_or(defaults.height_m, |v| v as f32),
+    };
+    Ok((level, config))
+}
+
+/// Steps a campaign level headlessly (as the game does) and prints the shot as JSON.
+/// `--wind` sets the Earth levels' wind, which the game otherwise randomises.
+fn run_simulate(args: &[String], options: &CliOptions) -> Result<(), CliError> {
+    let earth_wind_mps2 = options.atmosphere.wind_accel_x_mps2 as f32;
+    let levels = Level::campaign(|| earth_wind_mps2);
+    let (level, config) = parse_simulate_args(args, &levels)?;
+    let fields = [
+        ("angle_deg", config.angle_deg),
+        ("speed_mps", config.speed_mps),
+        ("height_m", config.height_m),
+    ];
+    if let Some(&(field, value)) = fields.iter().find(|(_, value)| !value.is_finite()) {
+        return Err(BallisticsError::NonFiniteInput {
+            field,
+            value: f64::from(value),
+        }
+        .into());
+    }
+    if config.speed_mps < 0.0 {
+        return Err(BallisticsError::NegativeSpeed {
+            speed_mps: f64::from(config.speed_mps),
+        }
+        .into());
+    }
+
+    let integrator = options.integrator.unwrap_or(Integrator::SemiImplicitEuler);
+    let prediction = simulate_shot(config, &level, integrator, SHOT_MAX_STEPS);
+    println!("{}", simulation_json(&level, config, &prediction));
+    Ok(())
+}
+
+/// Searches every campaign level (or just `--level`) for winning launches. Earth
+/// levels are solved across the whole random wind range; fails if any case has none.
+fn run_solve_level(args: &[String], options: &CliOptions) -> Result<(), CliError> {
+    let code = match args {
+        [] => None,
+        [flag, code] if flag == "--level" => Some(code.as_str()),
+        _ => {
+            return Err(CliError::Input(
+                "solve-level takes only --level <code>.".to_string(),
+            ));
+        }
+    };
+    let campaigns: Vec<(f32, Vec<Level>)> = earth_wind_samples(SOLVER_WINDS_PER_DIRECTION)
+        .into_iter()
+        .map(|wind| (wind, Level::campaign(|| wind)))
+        .collect();
+    let codes: Vec<&str> = match code {
+        Some(code) => {
+            let level = Level::find(&campaigns[0].1, code)
+                .ok_or_else(|| format!("Unknown level: '{code}'."))?;
+            vec![level.code.as_str()]
+        }
+        None => campaigns[0]
+            .1
+            .iter()
+            .map(|level| level.code.as_str())
+            .collect(),
+    };
+    let settings = SolverSettings {
+        integrator: options.integrator.unwrap_or(Integrator::SemiImplicitEuler),
+        ..SolverSettings::default()
+    };
+
+    let mut unsolvable = 0;
+    for code in codes {
+        let mut solved: Vec<&Level> = Vec::new();
+        for (_, levels) in &campaigns {
+            let level = Level::find(levels, code).expect("every campaign has the same levels");
+            if solved.contains(&level) {
+                continue;
+            }
+            solved.push(level);
+
+            let label = format!(
+                "{} (wind {:+.2} m/s^2)",
+                level.code, level.environment.wind_accel_x_mps2
+            );
+            match solve_level(level, &settings) {
+                Ok(solutions) => {
+                    let best = solutions[0];
+                    println!(
+                        "{label}: {} solutions; best angle {:.2} deg, speed {:.2} m/s, height {:.2} m ({} bounces, {:.2} s, miss {:.2} m)",
+                        solutions.len(),
+                        best.config.angle_deg,
+                        best.config.speed_mps,
+                        best.config.height_m,
+                        best.bounces,
+                        best.flight_time_s,
+                        best.miss_m
+                    );
+                }
+                Err(err) => {
+                    unsolvable += 1;
+                    println!("{label}: {err}");
+                }
+            }
+        }
+    }
+
+    if unsolvable > 0 {
+        return Err(CliError::Unsolvable(format!(
+            "{unsolvable} level/wind case(s) have no winning launch."
+        )));
+    }
+    Ok(())
+}
+
 fn axis_bounds(points: &[(f64, f64)]) -> ((f64, f64), (f64, f64)) {
-    let max_x = points
+    let (min_x, max_x) = points
         .iter()
-        .map(|(x, _)| *x)
-        .fold(f64::NEG_INFINITY, f64::max)
-        .max(0.0);
-    let max_y = points
+        .fold((0.0, 0.0), |(lo, hi): (f64, f64), (x, _)| {
+            (lo.min(*x), hi.max(*x))
+        });
+    let (min_y, max_y) = points
         .iter()
-        .map(|(_, y)| *y)
-        .fold(f64::NEG_INFINITY, f64::max)
-        .max(0.0);
-
-    let (x_span, y_span) = fixed_ratio_axis_window_f64(max_x, max_y);
+        .fold((0.0, 0.0), |(lo, hi): (f64, f64), (_, y)| {
+            (lo.min(*y), hi.max(*y))
+        });
 
-    let x_min = 0.0;
-    let x_max = x_span;
-    let y_min = 0.0;
-    let y_max = y_span;
-
-    ((x_min, x_max), (y_min, y_max))
+    fixed_ratio_axis_bounds(min_x, max_x, min_y, max_y)
 }
 
 fn format_value_for_filename(value: f64) -> String {
-    let rounded = (value * 100.0).round() / 100.0;
+    format_value_for_filename_with_decimals(value, 2)
+}
+
+fn format_value_for_filename_with_decimals(value: f64, decimals: usize) -> String {
+    let scale = 10f64.powi(decimals as i32);
+    let rounded = (value * scale).round() / scale;
     let mut s = if rounded.fract().abs() < 1e-9 {
         format!("{rounded:.0}")
     } else {
-        format!("{rounded:.2}")
+        format!("{rounded:.decimals$}")
             .trim_end_matches('0')
             .trim_end_matches('.')
             .to_string()
@@ -104,28 +657,111 @@ fn format_value_for_filename(value: f64) -> String {
     s.replace('.', "p")
 }
 
-fn build_output_image_name(inputs: Inputs) -> String {
+fn date_stamp() -> String {
     let now = Local::now();
     let yy = ((now.year() % 100) + 100) % 100;
+    format!("{}-{}-{:02}", now.month(), now.day(), yy)
+}
+
+fn gravity_tag(gravity_mps2: f64) -> String {
+    if gravity_mps2 == EARTH_GRAVITY_MPS2 {
+        String::new()
+    } else {
+        format!("_G{}", format_value_for_filename(gravity_mps2))
+    }
+}
+
+fn build_output_image_name(inputs: Inputs, options: &CliOptions) -> String {
+    let atmosphere = options.atmosphere;
+    let gravity_tag = gravity_tag(inputs.gravity_mps2);
+    let drag_tag = match atmosphere.drag {
+        DragModel::None => String::new(),
+        DragModel::Linear { k_per_s } => {
+            format!("_DL{}", format_value_for_filename_with_decimals(k_per_s, 5))
+        }
+        DragModel::Quadratic { k_per_m } => {
+            format!("_DQ{}", format_value_for_filename_with_decimals(k_per_m, 5))
+        }
+    };
+    let wind_tag = if atmosphere.wind_accel_x_mps2 == 0.0 {
+        String::new()
+    } else {
+        format!(
+            "_W{}",
+            format_value_for_filename(atmosphere.wind_accel_x_mps2)
+        )
+    };
+    let terrain_tag = match &options.ground {
+        None => String::new(),
+        Some(GroundProfile::Incline { slope_deg, .. }) => {
+            format!("_S{}", format_value_for_filename(*slope_deg))
+        }
+        Some(_) => "_T".to_string(),
+    };
+    let origin_tag = if inputs.launch_x_m == 0.0 {
+        String::new()
+    } else {
+        format!("_X{}", format_value_for_filename(inputs.launch_x_m))
+    };
+    let landing_tag = if inputs.landing_height_m == 0.0 {
+        String::new()
+    } else {
+        format!("_L{}", format_value_for_filename(inputs.landing_height_m))
+    };
     format!(
-        "A{}_V{}_H{}_trajectory_{}-{}-{:02}.png",
+        "A{}_V{}_H{}{}{}{}{}{}{}_trajectory_{}.png",
         format_value_for_filename(inputs.angle_deg),
         format_value_for_filename(inputs.speed_mps),
         format_value_for_filename(inputs.height_m),
-        now.month(),
-        now.day(),
-        yy
+        origin_tag,
+        landing_tag,
+        gravity_tag,
+        drag_tag,
+        wind_tag,
+        terrain_tag,
+        date_stamp()
     )
 }
 
+fn build_envelope_image_name(speed_mps: f64, height_m: f64, gravity_mps2: f64) -> String {
+    format!(
+        "V{}_H{}{}_envelope_{}.png",
+        format_value_for_filename(speed_mps),
+        format_value_for_filename(height_m),
+        gravity_tag(gravity_mps2),
+        date_stamp()
+    )
+}
+
+fn sample_for_plot(inputs: Inputs, options: &CliOptions, time_of_flight_s: f64) -> Vec<(f64, f64)> {
+    match options.integrator {
+        Some(integrator) => sample_trajectory_integrated(
+            inputs,
+            options.atmosphere,
+            integrator,
+            options.step_s,
+            time_of_flight_s,
+            TRAJECTORY_SAMPLES,
+        ),
+        None => sample_trajectory_with_drag(
+            inputs,
+            options.atmosphere,
+            time_of_flight_s,
+            TRAJECTORY_SAMPLES,
+        ),
+    }
+}
+
 fn save_trajectory_plot(
     inputs: Inputs,
+    points: &[(f64, f64)],
+    apex: Apex,
     time_of_flight_s: f64,
     horizontal_distance_m: f64,
+    ground: Option<&GroundProfile>,
     output_path: &str,
 ) -> Result<(), String> {
-    let points = sample_trajectory(inputs, time_of_flight_s, TRAJECTORY_SAMPLES);
-    let ((x_min, x_max), (y_min, y_max)) = axis_bounds(&points);
+    let ((x_min, x_max), (y_min, y_max)) = axis_bounds(points);
 
     let launch = points.first().copied().unwrap_or((0.0, inputs.height_m));
     let landing = points.last().copied().unwrap_or((0.0, 0.0));
@@ -154,6 +790,19 @@ fn save_trajectory_plot(
         .draw()
         .map_err(|e| format!("Failed to draw grid/axes: {e:?}"))?;
 
+    if let Some(ground) = ground {
+        let terrain = (0..=TERRAIN_SAMPLES).map(|i| {
+            let x = x_min + ((x_max - x_min) * i as f64 / TERRAIN_SAMPLES as f64);
+            (x, ground.height_at(x))
+        });
+        chart
+            .draw_series(
+                AreaSeries::new(terrain, y_min, RGBColor(160, 120, 80).mix(0.35))
+                    .border_style(RGBColor(120, 85, 50)),
+            )
+            .map_err(|e| format!("Failed to draw terrain: {e:?}"))?;
+    }
+
     chart
         .draw_series(LineSeries::new(
             points.iter().copied(),
@@ -168,6 +817,19 @@ fn save_trajectory_plot(
         ))
         .map_err(|e| format!("Failed to draw ground reference line: {e:?}"))?;
 
+    if ground.is_none() && inputs.landing_height_m != 0.0 {
+        let half_width = 0.04 * (x_max - x_min);
+        chart
+            .draw_series(LineSeries::new(
+                [
+                    (landing.0 - half_width, inputs.landing_height_m),
+                    (landing.0 + half_width, inputs.landing_height_m),
+                ],
+                ShapeStyle::from(&BLACK.mix(0.7)).stroke_width(4),
+            ))
+            .map_err(|e| format!("Failed to draw landing platform: {e:?}"))?;
+    }
+
     chart
         .draw_series(std::iter::once(Circle::new(launch, 5, RED.filled())))
         .map_err(|e| format!("Failed to draw launch point: {e:?}"))?;
@@ -199,6 +861,104 @@ fn save_trajectory_plot(
         )))
         .map_err(|e| format!("Failed to draw flight-time label: {e:?}"))?;
 
+    let apex_point = (apex.x_m, apex.height_m);
+    chart
+        .draw_series(std::iter::once(TriangleMarker::new(
+            apex_point,
+            7,
+            RGBColor(230, 126, 34).filled(),
+        )))
+        .map_err(|e| format!("Failed to draw apex point: {e:?}"))?;
+
+    let apex_label = format!("Apex: {:.2} m at {:.1} s", apex.height_m, apex.time_s);
+    let apex_label_x = (apex.x_m + (0.01 * x_span)).min(x_max - (0.15 * x_span));
+    let apex_label_y = (apex.height_m + (0.04 * y_span)).min(y_max - (0.02 * y_span));
+    chart
+        .draw_series(std::iter::once(Text::new(
+            apex_label,
+            (apex_label_x, apex_label_y),
+            ("Segoe UI", 16).into_font(),
+        )))
+        .map_err(|e| format!("Failed to draw apex label: {e:?}"))?;
+
+    root.present()
+        .map_err(|e| format!("Failed to write image file: {e:?}"))?;
+
+    Ok(())
+}
+
+fn save_envelope_plot(
+    envelope: &[(f64, f64)],
+    fan: &[Vec<(f64, f64)>],
+    output_path: &str,
+) -> Result<(), String> {
+    // Every arc stays under the envelope, so the envelope alone sets the window.
+    let ((x_min, x_max), (y_min, y_max)) = axis_bounds(envelope);
+
+    let root = BitMapBackend::new(output_path, (PLOT_WIDTH, PLOT_HEIGHT)).into_drawing_area();
+    root.fill(&WHITE)
+        .map_err(|e| format!("Failed to clear plotting area: {e:?}"))?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .margin(CHART_MARGIN)
+        .x_label_area_size(X_LABEL_AREA)
+        .y_label_area_size(Y_LABEL_AREA)
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)
+        .map_err(|e| format!("Failed to build plot axes: {e:?}"))?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Horizontal Distance (m)")
+        .y_desc("Height (m)")
+        .x_labels(12)
+        .y_labels(12)
+        .axis_desc_style(("Segoe UI", 20).into_font())
+        .label_style(("Segoe UI", 14).into_font())
+        .light_line_style(RGBColor(220, 220, 220))
+        .bold_line_style(RGBColor(190, 190, 190))
+        .draw()
+        .map_err(|e| format!("Failed to draw grid/axes: {e:?}"))?;
+
+    for arc in fan {
+        chart
+            .draw_series(LineSeries::new(
+                arc.iter().copied().filter(|(_, y)| *y >= 0.0),
+                &BLUE.mix(0.45),
+            ))
+            .map_err(|e| format!("Failed to draw fan trajectory: {e:?}"))?;
+    }
+
+    chart
+        .draw_series(DashedLineSeries::new(
+            envelope.iter().copied(),
+            10,
+            6,
+            ShapeStyle::from(&RED).stroke_width(TRAJECTORY_LINE_WIDTH),
+        ))
+        .map_err(|e| format!("Failed to draw safety envelope: {e:?}"))?;
+
+    chart
+        .draw_series(LineSeries::new(
+            [(x_min, 0.0), (x_max, 0.0)],
+            &BLACK.mix(0.4),
+        ))
+        .map_err(|e| format!("Failed to draw ground reference line: {e:?}"))?;
+
+    if let Some(&reach) = envelope.last() {
+        let x_span = x_max - x_min;
+        let y_span = y_max - y_min;
+        chart
+            .draw_series(std::iter::once(Text::new(
+                format!("Envelope reach: {:.2} m", reach.0),
+                (
+                    (reach.0 + (0.01 * x_span)).min(x_max - (0.15 * x_span)),
+                    reach.1 + (0.04 * y_span),
+                ),
+                ("Segoe UI", 16).into_font(),
+            )))
+            .map_err(|e| format!("Failed to draw envelope label: {e:?}"))?;
+    }
+
     root.present()
         .map_err(|e| format!("Failed to write image file: {e:?}"))?;
 
@@ -209,16 +969,139 @@ fn print_usage(program: &str) {
     println!("Usage:");
     println!("  {program}");
     println!("  {program} <angle_deg> <velocity_mps> <height_m>");
+    println!("  {program} solve <velocity_mps> <height_m> <target_x_m> <target_y_m>");
+    println!("  {program} min-speed <height_m> <target_x_m> <target_y_m>");
+    println!("  {program} optimal <velocity_mps> <height_m>");
+    println!("  {program} envelope <velocity_mps> <height_m>");
+    println!("  {program} simulate --level <code> [--angle <deg>] [--speed <m/s>] [--height <m>]");
+    println!("  {program} solve-level [--level <code>]");
+    println!();
+    println!("Options:");
+    println!("  --body <name>       use a named body's gravity (Earth, Moon, Mars, Jupiter, ...)");
+    println!("  --gravity <m/s^2>   use a custom gravity value");
+    println!("  --drag-linear <k>   linear drag a = -k*v (1/s), as in the game's Earth levels");
+    println!("  --drag-quadratic <k>  quadratic drag a = -k*|v|*v (1/m)");
+    println!("  --wind <m/s^2>      constant horizontal wind acceleration");
+    println!("  --integrator <name> step numerically with euler, semi-implicit, rk4 or rk45");
+    println!("  --step <s>          integration step for --integrator (default 1/240 s)");
+    println!("  --mass <kg>         projectile mass, adds impact kinetic energy and momentum");
+    println!("  --launch-x <m>      horizontal position of the launch point");
+    println!("  --landing-height <m> land on a platform at this elevation (may be above launch)");
+    println!("  --slope <deg>       land on an incline through the origin");
+    println!("  --terrain <x:y,...> land on piecewise-linear ground");
+    println!();
+    println!("Exit codes:");
+    println!("  2 invalid input, 3 plot output failed, 4 a level has no winning launch");
+    println!("  10-16 rejected parameters, 20-24 no valid landing, 30-31 target unreachable");
     println!();
     println!("Examples:");
     println!("  {program}");
     println!("  {program} 45 30 1.5");
+    println!("  {program} solve 30 1.5 60 10");
+    println!("  {program} 20 90 20 --body moon");
+    println!("  {program} 34 56 2 --drag-linear 0.015 --wind 0.5");
+    println!("  {program} 45 30 1.5 --integrator rk4 --step 0.01");
+    println!("  {program} 30 120 10 --mass 0.145");
+    println!("  {program} min-speed 1.5 60 10 --gravity 3.71");
+    println!("  {program} optimal 60 20 --drag-quadratic 0.001");
+    println!("  {program} envelope 40 10 --body mars");
+    println!("  {program} 40 35 2 --terrain 0:0,40:3,60:12,120:20");
+    println!("  {program} 55 30 1.5 --launch-x -20 --landing-height 12");
+    println!(
+        "  {program} simulate --level \"EARTH 3\" --angle 30 --speed 64 --height 2.5 --wind -0.8"
+    );
     println!();
     println!("The program saves a PNG plot named like:");
     println!("  A75_V150_H600_trajectory_2-16-26.png");
 }
 
-fn run() -> Result<(), String> {
+/// Prints the flight report for `inputs` and saves its plot.
+fn run_trajectory(inputs: Inputs, options: &CliOptions) -> Result<(), CliError> {
+    let summary = match (&options.ground, options.integrator) {
+        (Some(_), Some(_)) => {
+            return Err(CliError::Input(
+                "--integrator cannot be combined with --slope/--terrain.".to_string(),
+            ));
+        }
+        (Some(_), None) if options.landing_height_m != 0.0 => {
+            return Err(CliError::Input(
+                "--landing-height cannot be combined with --slope/--terrain.".to_string(),
+            ));
+        }
+        (Some(ground), None) => {
+            flight_summary_on_ground(inputs, options.atmosphere, ground, options.mass_kg)?
+        }
+        (None, Some(integrator)) => flight_summary_integrated(
+            inputs,
+            options.atmosphere,
+            integrator,
+            options.step_s,
+            options.mass_kg,
+        )?,
+        (None, None) => flight_summary_with_drag(inputs, options.atmosphere, options.mass_kg)?,
+    };
+    let (time, distance) = (summary.time_s, summary.range_m);
+    let output_image = build_output_image_name(inputs, options);
+
+    let apex = apex_with_drag(inputs, options.atmosphere)?;
+
+    println!("\nTime of flight: {:.4} s", time);
+    println!("Horizontal distance: {:.4} m", distance);
+    if let Some(ground) = &options.ground {
+        println!("Landing height: {:.4} m", ground.height_at(distance));
+    }
+    println!("Apex height: {:.4} m", apex.height_m);
+    println!("Time to apex: {:.4} s", apex.time_s);
+    println!("Apex distance: {:.4} m", apex.x_m);
+    println!(
+        "Impact velocity: vx {:.4} m/s, vy {:.4} m/s",
+        summary.impact_vx_mps, summary.impact_vy_mps
+    );
+    println!("Impact speed: {:.4} m/s", summary.impact_speed_mps);
+    println!(
+        "Impact angle: {:.4} deg below horizontal",
+        summary.impact_angle_deg
+    );
+    if let Some(energy) = summary.kinetic_energy_j {
+        println!("Impact kinetic energy: {:.4} J", energy);
+    }
+    if let Some(momentum) = summary.momentum_kg_mps {
+        println!("Impact momentum: {:.4} kg*m/s", momentum);
+    }
+
+    if let Some(integrator) = options.integrator {
+        println!(
+            "Integrator: {} (step {:.6} s)",
+            integrator.name(),
+            options.step_s
+        );
+        if options.atmosphere.is_vacuum() {
+            let (exact_time, exact_distance) = flight_time_and_range(inputs)?;
+            println!(
+                "Error vs analytic parabola: time {:+.3e} s, distance {:+.3e} m",
+                time - exact_time,
+                distance - exact_distance
+            );
+        }
+    }
+
+    let points = sample_for_plot(inputs, options, time);
+    save_trajectory_plot(
+        inputs,
+        &points,
+        apex,
+        time,
+        distance,
+        options.ground.as_ref(),
+        &output_image,
+    )
+    .map_err(CliError::Plot)?;
+    println!("Saved plot: {output_image}");
+
+    Ok(())
+}
+
+fn run() -> Result<(), CliError> {
     let args: Vec<String> = env::args().collect();
 
     if args.iter().any(|a| a == "-h" || a == "--help") {
@@ -226,35 +1109,66 @@ fn run() -> Result<(), String> {
         return Ok(());
     }
 
-    let inputs = if args.len() == 1 {
-        get_inputs_from_user()?
+    let (positional, options) = split_options(&args[1..])?;
+    match positional.first().map(String::as_str) {
+        Some("solve") => return run_solve(&positional[1..], &options),
+        Some("min-speed") => return run_min_speed(&positional[1..], &options),
+        Some("optimal") => return run_optimal(&positional[1..], &options),
+        Some("envelope") => return run_envelope(&positional[1..], &options),
+        Some("simulate") => return run_simulate(&positional[1..], &options),
+        Some("solve-level") => return run_solve_level(&positional[1..], &options),
+        _ => {}
+    }
+
+    let inputs = if positional.is_empty() {
+        get_inputs_from_user(&options)?
     } else {
-        get_inputs_from_args(&args)?
+        get_inputs_from_args(&positional, &options)?
     };
 
-    let (time, distance) = flight_time_and_range(inputs)?;
-    let output_image = build_output_image_name(inputs);
-
-    println!("\nTime of flight: {:.4} s", time);
-    println!("Horizontal distance: {:.4} m", distance);
-
-    save_trajectory_plot(inputs, time, distance, &output_image)?;
-    println!("Saved plot: {output_image}");
-
-    Ok(())
+    run_trajectory(inputs, &options)
 }
 
 fn main() {
     if let Err(err) = run() {
         eprintln!("Error: {err}");
-        print_usage("cargo run --");
-        std::process::exit(1);
+        if matches!(err, CliError::Input(_)) {
+            print_usage("cargo run --");
+        }
+        std::process::exit(err.exit_code());
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Inputs, axis_bounds, flight_time_and_range, sample_trajectory};
+    use super::{
+        CliError, Inputs, axis_bounds, envelope_fan, launch_angles_to_target,
+        minimum_speed_to_target, parse_simulate_args, simulation_json,
+    };
+    use glam::{Vec2, vec2};
+    use parabolic_rust::core::ballistics::{
+        BallisticsError, Body, EARTH_GRAVITY_MPS2, apex, flight_summary, flight_time_and_range,
+        launch_velocity, optimal_launch_angle, safety_envelope_height, sample_safety_envelope,
+        sample_trajectory, trajectory_at_time,
+    };
+    use parabolic_rust::core::campaign::builtin_campaign_files;
+    use parabolic_rust::core::drag::{
+        Atmosphere, DragModel, apex_with_drag, flight_time_and_range_integrated,
+        flight_time_and_range_with_drag, optimal_launch_angle_with_drag,
+        sample_trajectory_with_drag, trajectory_at_time_with_drag,
+    };
+    use parabolic_rust::core::integrate::{FlightState, Integrator};
+    use parabolic_rust::core::level_file::{parse_campaign, parse_manifest};
+    use parabolic_rust::core::sim::{
+        Barrier, BounceSurface, DEFAULT_SURFACE_DAMPING, FIXED_STEP_S, LaunchConfig, Level,
+        PROJECTILE_RADIUS_M, Projectile, Rect, SHOT_MAX_STEPS, StepOutcome, SurfaceMotion,
+        TargetMotion, launch_projectile, simulate_prediction, simulate_shot, step_projectile,
+    };
+    use parabolic_rust::core::solver::{
+        NearestSearch, SolverSettings, nearest_solution, solve_level,
+    };
+    use parabolic_rust::core::terrain::{GroundProfile, landing_on_ground};
+    use parabolic_rust::core::window::fixed_ratio_axis_window;
 
     fn assert_close(actual: f64, expected: f64, tolerance: f64) {
         assert!(
@@ -263,12 +1177,57 @@ mod tests {
         );
     }
 
+    /// EARTH 1 without drag and with its target parked out of reach, as a blank
+    /// range for surface and obstacle tests.
+    fn open_range_level() -> Level {
+        let mut level = Level::campaign(|| 0.0)[0].clone();
+        level.environment.drag_linear = 0.0;
+        level.target.center = vec2(500.0, 500.0);
+        level
+    }
+
+    /// A fixed, frictionless, undamped box surface centred on `center`, with
+    /// restitution 0.5; tests override the fields they exercise.
+    fn slab(center: Vec2, half_size: Vec2) -> BounceSurface {
+        BounceSurface {
+            corners: [
+                center + vec2(-half_size.x, half_size.y),
+                center + half_size,
+                center + vec2(half_size.x, -half_size.y),
+                center - half_size,
+            ],
+            restitution: 0.5,
+            friction: 0.0,
+            damping: 0.0,
+            motion: SurfaceMotion::Fixed,
+        }
+    }
+
+    /// Steps `projectile` to its first bounce and returns its state one step before;
+    /// panics rather than hangs if it never bounces.
+    fn step_to_first_bounce(projectile: &mut Projectile, level: &Level) -> Projectile {
+        for _ in 0..SHOT_MAX_STEPS {
+            let before = *projectile;
+            step_projectile(
+                projectile,
+                level,
+                FIXED_STEP_S,
+                Integrator::SemiImplicitEuler,
+            );
+            if projectile.bounces > 0 {
+                return before;
+            }
+        }
+        panic!("the shot never bounced");
+    }
+
     #[test]
     fn computes_known_range_for_flat_ground() {
         let (time, distance) = flight_time_and_range(Inputs {
             angle_deg: 45.0,
             speed_mps: 10.0,
             height_m: 0.0,
+            ..Inputs::default()
         })
         .expect("calculation should succeed");
 
@@ -282,6 +1241,7 @@ mod tests {
             angle_deg: 10.0,
             speed_mps: 0.0,
             height_m: 2.0,
+            ..Inputs::default()
         })
         .expect("calculation should succeed");
 
@@ -295,10 +1255,1251 @@ mod tests {
             angle_deg: 0.0,
             speed_mps: 1.0,
             height_m: -10.0,
+            ..Inputs::default()
         })
         .expect_err("calculation should fail");
 
-        assert!(err.contains("No real landing time"));
+        assert!(
+            matches!(err, BallisticsError::NoRealLanding { discriminant } if discriminant < 0.0)
+        );
+    }
+
+    #[test]
+    fn solved_angles_pass_through_target() {
+        let solutions = launch_angles_to_target(30.0, 1.5, 60.0, 10.0, 9.8)
+            .expect("target should be reachable");
+        assert!(solutions.low_deg < solutions.high_deg);
+
+        for (angle_deg, time_s) in [
+            (solutions.low_deg, solutions.low_time_s),
+            (solutions.high_deg, solutions.high_time_s),
+        ] {
+            let inputs = Inputs {
+                angle_deg,
+                speed_mps: 30.0,
+                height_m: 1.5,
+                ..Inputs::default()
+            };
+            let (x, y) = trajectory_at_time(inputs, time_s);
+            assert_close(x, 60.0, 1e-6);
+            assert_close(y, 10.0, 1e-6);
+        }
+    }
+
+    #[test]
+    fn reports_unreachable_target() {
+        let err =
+            launch_angles_to_target(10.0, 0.0, 100.0, 0.0, 9.8).expect_err("should be too far");
+        assert!(matches!(err, BallisticsError::OutOfReach { .. }));
+    }
+
+    #[test]
+    fn minimum_speed_is_where_angle_solutions_merge() {
+        let solution =
+            minimum_speed_to_target(1.5, 60.0, 10.0, 9.8).expect("minimum speed should exist");
+        let solutions = launch_angles_to_target(solution.speed_mps + 1e-9, 1.5, 60.0, 10.0, 9.8)
+            .expect("target should be just reachable");
+
+        assert_close(solutions.low_deg, solution.angle_deg, 1e-2);
+        assert_close(solutions.high_deg, solution.angle_deg, 1e-2);
+        assert!(launch_angles_to_target(solution.speed_mps - 1e-3, 1.5, 60.0, 10.0, 9.8).is_err());
+    }
+
+    #[test]
+    fn minimum_speed_scales_with_gravity() {
+        let earth = minimum_speed_to_target(0.0, 100.0, 0.0, 9.8).expect("earth solution");
+        let moon = minimum_speed_to_target(0.0, 100.0, 0.0, 1.62).expect("moon solution");
+
+        assert_close(earth.angle_deg, 45.0, 1e-9);
+        assert_close(moon.angle_deg, 45.0, 1e-9);
+        assert_close(earth.speed_mps, (9.8f64 * 100.0).sqrt(), 1e-9);
+        assert_close(moon.speed_mps, (1.62f64 * 100.0).sqrt(), 1e-9);
+    }
+
+    #[test]
+    fn moon_gravity_stretches_flight() {
+        let earth = Inputs {
+            angle_deg: 45.0,
+            speed_mps: 10.0,
+            height_m: 0.0,
+            ..Inputs::default()
+        };
+        let moon = Inputs {
+            gravity_mps2: Body::Moon.gravity_mps2(),
+            ..earth
+        };
+        let (earth_time, earth_range) = flight_time_and_range(earth).expect("earth flight");
+        let (moon_time, moon_range) = flight_time_and_range(moon).expect("moon flight");
+
+        let ratio = EARTH_GRAVITY_MPS2 / 1.62;
+        assert_close(moon_time, earth_time * ratio, 1e-9);
+        assert_close(moon_range, earth_range * ratio, 1e-9);
+    }
+
+    #[test]
+    fn rejects_non_positive_gravity() {
+        let err = flight_time_and_range(Inputs {
+            angle_deg: 45.0,
+            speed_mps: 10.0,
+            gravity_mps2: 0.0,
+            ..Inputs::default()
+        })
+        .expect_err("calculation should fail");
+
+        assert_eq!(
+            err,
+            BallisticsError::NonPositiveGravity { gravity_mps2: 0.0 }
+        );
+    }
+
+    #[test]
+    fn non_finite_inputs_name_the_offending_field() {
+        let err = flight_time_and_range(Inputs {
+            angle_deg: 45.0,
+            speed_mps: 10.0,
+            height_m: f64::INFINITY,
+            ..Inputs::default()
+        })
+        .expect_err("calculation should fail");
+
+        assert_eq!(
+            err,
+            BallisticsError::NonFiniteInput {
+                field: "height_m",
+                value: f64::INFINITY,
+            }
+        );
+        assert!(err.to_string().contains("height_m = inf"));
+    }
+
+    #[test]
+    fn weak_linear_drag_approaches_vacuum() {
+        let inputs = Inputs {
+            angle_deg: 40.0,
+            speed_mps: 50.0,
+            height_m: 2.0,
+            ..Inputs::default()
+        };
+        let (vacuum_time, vacuum_range) = flight_time_and_range(inputs).expect("vacuum flight");
+        let (drag_time, drag_range) = flight_time_and_range_with_drag(
+            inputs,
+            Atmosphere {
+                drag: DragModel::Linear { k_per_s: 1e-9 },
+                wind_accel_x_mps2: 0.0,
+            },
+        )
+        .expect("drag flight");
+
+        assert_close(drag_time, vacuum_time, 1e-5);
+        assert_close(drag_range, vacuum_range, 1e-4);
+    }
+
+    #[test]
+    fn drag_shortens_range_and_lands_on_ground() {
+        let inputs = Inputs {
+            angle_deg: 34.0,
+            speed_mps: 56.0,
+            height_m: 2.0,
+            ..Inputs::default()
+        };
+        let (_, vacuum_range) = flight_time_and_range(inputs).expect("vacuum flight");
+
+        for drag in [
+            DragModel::Linear { k_per_s: 0.015 },
+            DragModel::Quadratic { k_per_m: 0.002 },
+        ] {
+            let atmosphere = Atmosphere {
+                drag,
+                wind_accel_x_mps2: 0.0,
+            };
+            let (time, range) =
+                flight_time_and_range_with_drag(inputs, atmosphere).expect("drag flight");
+            let points = sample_trajectory_with_drag(inputs, atmosphere, time, 200);
+            let last = points.last().copied().expect("has last point");
+
+            assert!(range < vacuum_range, "{drag:?} should shorten the range");
+            assert_close(last.0, range, 1e-3);
+            assert_close(last.1, 0.0, 1e-3);
+        }
+    }
+
+    #[test]
+    fn integrators_converge_on_analytic_parabola() {
+        let inputs = Inputs {
+            angle_deg: 45.0,
+            speed_mps: 30.0,
+            height_m: 1.5,
+            ..Inputs::default()
+        };
+        let (exact_time, exact_range) = flight_time_and_range(inputs).expect("analytic flight");
+
+        for (integrator, tolerance) in [
+            (Integrator::Euler, 0.5),
+            (Integrator::SemiImplicitEuler, 0.5),
+            (Integrator::Rk4, 1e-6),
+            (
+                Integrator::DormandPrince {
+                    tolerance: Integrator::DEFAULT_TOLERANCE,
+                },
+                1e-6,
+            ),
+        ] {
+            let (time, range) =
+                flight_time_and_range_integrated(inputs, Atmosphere::VACUUM, integrator, 0.01)
+                    .expect("integrated flight");
+            assert_close(time, exact_time, tolerance);
+            assert_close(range, exact_range, tolerance);
+        }
+    }
+
+    #[test]
+    fn adaptive_integrator_matches_linear_drag_closed_form() {
+        let inputs = Inputs {
+            angle_deg: 34.0,
+            speed_mps: 56.0,
+            height_m: 2.0,
+            ..Inputs::default()
+        };
+        let atmosphere = Atmosphere {
+            drag: DragModel::Linear { k_per_s: 0.015 },
+            wind_accel_x_mps2: -0.6,
+        };
+        let (exact_time, exact_range) =
+            flight_time_and_range_with_drag(inputs, atmosphere).expect("closed-form flight");
+        let (time, range) = flight_time_and_range_integrated(
+            inputs,
+            atmosphere,
+            Integrator::DormandPrince { tolerance: 1e-10 },
+            0.25,
+        )
+        .expect("integrated flight");
+
+        assert_close(time, exact_time, 1e-6);
+        assert_close(range, exact_range, 1e-5);
+    }
+
+    #[test]
+    fn adaptive_integrator_always_advances_the_full_step() {
+        // A stiff spring in y forces tiny substeps, far more than the substep cap
+        // allows over 1 s; x moves at 1 m/s, so it records how far time got.
+        let start = FlightState {
+            x_m: 0.0,
+            y_m: 1.0,
+            vx_mps: 1.0,
+            vy_mps: 0.0,
+        };
+        let end = Integrator::DormandPrince { tolerance: 1e-9 }
+            .advance(start, 1.0, |s| (0.0, -1e8 * s.y_m));
+        assert_close(end.x_m, 1.0, 1e-9);
+    }
+
+    #[test]
+    fn apex_matches_known_values() {
+        let inputs = Inputs {
+            angle_deg: 90.0,
+            speed_mps: 9.8,
+            height_m: 2.0,
+            ..Inputs::default()
+        };
+        let top = apex(inputs).expect("apex should exist");
+        assert_close(top.time_s, 1.0, 1e-9);
+        assert_close(top.height_m, 2.0 + 4.9, 1e-9);
+        assert_close(top.x_m, 0.0, 1e-9);
+
+        let downward = apex(Inputs {
+            angle_deg: -20.0,
+            ..inputs
+        })
+        .expect("apex should exist");
+        assert_close(downward.time_s, 0.0, 1e-12);
+        assert_close(downward.height_m, 2.0, 1e-12);
+    }
+
+    #[test]
+    fn drag_apex_is_lower_and_stationary() {
+        let inputs = Inputs {
+            angle_deg: 60.0,
+            speed_mps: 40.0,
+            height_m: 0.0,
+            ..Inputs::default()
+        };
+        let vacuum = apex(inputs).expect("vacuum apex");
+
+        for drag in [
+            DragModel::Linear { k_per_s: 0.05 },
+            DragModel::Quadratic { k_per_m: 0.002 },
+        ] {
+            let atmosphere = Atmosphere {
+                drag,
+                wind_accel_x_mps2: 0.0,
+            };
+            let top = apex_with_drag(inputs, atmosphere).expect("drag apex");
+            let points = sample_trajectory_with_drag(inputs, atmosphere, 2.0 * top.time_s, 400);
+            let sampled_max = points.iter().map(|p| p.1).fold(f64::MIN, f64::max);
+
+            assert!(
+                top.height_m < vacuum.height_m,
+                "{drag:?} should lower the apex"
+            );
+            assert_close(top.height_m, sampled_max, 1e-3);
+        }
+    }
+
+    #[test]
+    fn impact_summary_conserves_energy_in_vacuum() {
+        let inputs = Inputs {
+            angle_deg: 30.0,
+            speed_mps: 20.0,
+            height_m: 15.0,
+            ..Inputs::default()
+        };
+        let summary = flight_summary(inputs, Some(2.0)).expect("summary should succeed");
+
+        // v_impact^2 = v0^2 + 2*g*h
+        let expected_speed = (20.0f64.powi(2) + 2.0 * EARTH_GRAVITY_MPS2 * 15.0).sqrt();
+        assert_close(summary.impact_vx_mps, 20.0 * 30f64.to_radians().cos(), 1e-9);
+        assert_close(summary.impact_speed_mps, expected_speed, 1e-9);
+        assert!(summary.impact_angle_deg > 30.0 && summary.impact_angle_deg < 90.0);
+        assert_close(
+            summary.kinetic_energy_j.expect("mass given"),
+            0.5 * 2.0 * expected_speed * expected_speed,
+            1e-6,
+        );
+        assert_close(
+            summary.momentum_kg_mps.expect("mass given"),
+            2.0 * expected_speed,
+            1e-9,
+        );
+        assert!(
+            flight_summary(inputs, None)
+                .expect("summary")
+                .kinetic_energy_j
+                .is_none()
+        );
+        assert!(flight_summary(inputs, Some(-1.0)).is_err());
+    }
+
+    #[test]
+    fn optimal_angle_drops_below_45_when_elevated() {
+        let flat = optimal_launch_angle(30.0, 0.0, EARTH_GRAVITY_MPS2).expect("flat optimum");
+        assert_close(flat.angle_deg, 45.0, 1e-9);
+        assert_close(flat.range_m, 30.0 * 30.0 / EARTH_GRAVITY_MPS2, 1e-9);
+
+        let raised = optimal_launch_angle(30.0, 20.0, EARTH_GRAVITY_MPS2).expect("raised optimum");
+        assert!(raised.angle_deg < 45.0);
+        for nudge in [-1.0, 1.0] {
+            let inputs = Inputs {
+                angle_deg: raised.angle_deg + nudge,
+                speed_mps: 30.0,
+                height_m: 20.0,
+                ..Inputs::default()
+            };
+            let (_, range) = flight_time_and_range(inputs).expect("nudged range");
+            assert!(range < raised.range_m);
+        }
+    }
+
+    #[test]
+    fn numeric_optimal_angle_matches_analytic_and_drops_with_drag() {
+        let analytic = optimal_launch_angle(40.0, 10.0, EARTH_GRAVITY_MPS2).expect("vacuum");
+        let near_vacuum = optimal_launch_angle_with_drag(
+            40.0,
+            10.0,
+            EARTH_GRAVITY_MPS2,
+            Atmosphere {
+                drag: DragModel::None,
+                wind_accel_x_mps2: 1e-12,
+            },
+        )
+        .expect("near-vacuum optimum");
+        assert_close(near_vacuum.angle_deg, analytic.angle_deg, 1e-4);
+        assert_close(near_vacuum.range_m, analytic.range_m, 1e-4);
+
+        let draggy = optimal_launch_angle_with_drag(
+            40.0,
+            10.0,
+            EARTH_GRAVITY_MPS2,
+            Atmosphere {
+                drag: DragModel::Quadratic { k_per_m: 0.005 },
+                wind_accel_x_mps2: 0.0,
+            },
+        )
+        .expect("quadratic-drag optimum");
+        assert!(draggy.angle_deg < analytic.angle_deg);
+        assert!(draggy.range_m < analytic.range_m);
+    }
+
+    #[test]
+    fn safety_envelope_bounds_and_touches_every_arc() {
+        let (speed, height) = (25.0, 5.0);
+        for angle_deg in [10.0, 30.0, 50.0, 70.0] {
+            let inputs = Inputs {
+                angle_deg,
+                speed_mps: speed,
+                height_m: height,
+                ..Inputs::default()
+            };
+            let (time, _) = flight_time_and_range(inputs).expect("valid flight");
+            let mut closest_gap = f64::MAX;
+            for (x, y) in sample_trajectory(inputs, time, 2000) {
+                let envelope = safety_envelope_height(speed, height, EARTH_GRAVITY_MPS2, x)
+                    .expect("valid envelope");
+                let gap = envelope - y;
+                assert!(gap >= -1e-9, "arc at {angle_deg} deg crosses the envelope");
+                closest_gap = closest_gap.min(gap);
+            }
+            // Arcs flatter than the optimum land before reaching their tangent point.
+            if angle_deg > 45.0 {
+                assert!(closest_gap < 1e-3, "arc at {angle_deg} deg never touches");
+            }
+        }
+
+        let envelope = sample_safety_envelope(speed, height, EARTH_GRAVITY_MPS2, 100)
+            .expect("envelope samples");
+        let reach = optimal_launch_angle(speed, height, EARTH_GRAVITY_MPS2).expect("optimum");
+        assert_close(
+            envelope[0].1,
+            height + speed * speed / (2.0 * EARTH_GRAVITY_MPS2),
+            1e-9,
+        );
+        assert_close(envelope.last().unwrap().0, reach.range_m, 1e-9);
+        assert_close(envelope.last().unwrap().1, 0.0, 1e-9);
+    }
+
+    #[test]
+    fn safety_envelope_rejects_bad_inputs_and_unreachable_points() {
+        let g = EARTH_GRAVITY_MPS2;
+        assert_eq!(safety_envelope_height(0.0, 5.0, g, 0.0), Ok(5.0));
+        assert_eq!(
+            safety_envelope_height(0.0, 5.0, g, 3.0),
+            Err(BallisticsError::OutOfReach {
+                speed_mps: 0.0,
+                target_x_m: 3.0,
+                target_y_m: 5.0,
+            })
+        );
+        assert!(matches!(
+            safety_envelope_height(f64::NAN, 5.0, g, 3.0),
+            Err(BallisticsError::NonFiniteInput {
+                field: "speed_mps",
+                ..
+            })
+        ));
+        assert_eq!(
+            safety_envelope_height(10.0, 5.0, f64::INFINITY, 3.0),
+            Err(BallisticsError::NonFiniteInput {
+                field: "gravity_mps2",
+                value: f64::INFINITY,
+            })
+        );
+        assert_eq!(
+            safety_envelope_height(10.0, 5.0, 0.0, 3.0),
+            Err(BallisticsError::NonPositiveGravity { gravity_mps2: 0.0 })
+        );
+    }
+
+    #[test]
+    fn envelope_fan_skips_arcs_that_never_surface_from_below_ground() {
+        let (speed, height) = (30.0, -5.0);
+        sample_safety_envelope(speed, height, EARTH_GRAVITY_MPS2, 100).expect("envelope samples");
+        optimal_launch_angle(speed, height, EARTH_GRAVITY_MPS2).expect("optimum");
+
+        let fan = envelope_fan(speed, height, EARTH_GRAVITY_MPS2);
+        // Below ~19.3 deg the arc peaks under y = 0; the 22.5..=90 deg arcs remain.
+        assert_eq!(fan.len(), 10);
+        for arc in &fan {
+            assert_close(arc.last().unwrap().1, 0.0, 1e-9);
+        }
+    }
+
+    #[test]
+    fn lands_on_incline_where_parabola_meets_slope() {
+        let inputs = Inputs {
+            angle_deg: 50.0,
+            speed_mps: 30.0,
+            height_m: 1.0,
+            ..Inputs::default()
+        };
+        let slope = GroundProfile::Incline {
+            height_m: 0.0,
+            slope_deg: 10.0,
+        };
+        let landing = landing_on_ground(inputs, Atmosphere::VACUUM, &slope).expect("lands");
+
+        // h + x*tan(a) - g*x^2 / (2 v^2 cos^2 a) = x*tan(s)
+        let a = inputs.angle_deg.to_radians();
+        let qa = EARTH_GRAVITY_MPS2 / (2.0 * (30.0 * a.cos()).powi(2));
+        let qb = 10f64.to_radians().tan() - a.tan();
+        let expected_x = (-qb + (qb * qb + 4.0 * qa * inputs.height_m).sqrt()) / (2.0 * qa);
+        assert_close(landing.x_m, expected_x, 1e-6);
+        assert_close(landing.y_m, expected_x * 10f64.to_radians().tan(), 1e-6);
+        assert_close(landing.time_s, expected_x / (30.0 * a.cos()), 1e-6);
+
+        let as_function = |x: f64| x * 10f64.to_radians().tan();
+        let via_function =
+            landing_on_ground(inputs, Atmosphere::VACUUM, &as_function).expect("lands");
+        assert_close(via_function.x_m, landing.x_m, 1e-9);
+    }
+
+    #[test]
+    fn polyline_terrain_catches_first_ledge() {
+        let inputs = Inputs {
+            angle_deg: 45.0,
+            speed_mps: 30.0,
+            height_m: 0.0,
+            ..Inputs::default()
+        };
+        let (_, flat_range) = flight_time_and_range(inputs).expect("flat range");
+        let wall = GroundProfile::Polyline(vec![(0.0, 0.0), (40.0, 0.0), (40.0, 100.0)]);
+        let landing = landing_on_ground(inputs, Atmosphere::VACUUM, &wall).expect("hits wall");
+        assert!(flat_range > 40.0);
+        assert_close(landing.x_m, 40.0, 1e-6);
+        assert!(landing.y_m > 0.0);
+
+        let unsorted = GroundProfile::Polyline(vec![(0.0, 0.0), (10.0, 1.0), (5.0, 2.0)]);
+        let err =
+            landing_on_ground(inputs, Atmosphere::VACUUM, &unsorted).expect_err("unsorted terrain");
+        assert_eq!(err, BallisticsError::UnsortedTerrain { x_m: 5.0 });
+    }
+
+    #[test]
+    fn raised_landing_uses_descending_root_and_offset_shifts_x() {
+        let inputs = Inputs {
+            angle_deg: 60.0,
+            speed_mps: 25.0,
+            height_m: 1.0,
+            landing_height_m: 15.0,
+            ..Inputs::default()
+        };
+        let (time, distance) = flight_time_and_range(inputs).expect("lands on platform");
+        let apex_time = apex(inputs).expect("apex").time_s;
+        let (_, y) = trajectory_at_time(inputs, time);
+        assert!(time > apex_time, "must land on the way down");
+        assert_close(y, 15.0, 1e-9);
+
+        let shifted = Inputs {
+            launch_x_m: -30.0,
+            ..inputs
+        };
+        let (shifted_time, shifted_distance) =
+            flight_time_and_range(shifted).expect("shifted flight");
+        assert_close(shifted_time, time, 1e-12);
+        assert_close(shifted_distance, distance, 1e-12);
+        assert_close(trajectory_at_time(shifted, time).0, distance - 30.0, 1e-9);
+
+        let linear = Atmosphere {
+            drag: DragModel::Linear { k_per_s: 0.02 },
+            wind_accel_x_mps2: 0.0,
+        };
+        let (drag_time, _) = flight_time_and_range_with_drag(shifted, linear).expect("drag");
+        let (_, drag_y) = trajectory_at_time_with_drag(shifted, linear, drag_time);
+        assert_close(drag_y, 15.0, 1e-6);
+
+        let too_high = Inputs {
+            landing_height_m: 40.0,
+            ..inputs
+        };
+        assert!(matches!(
+            flight_time_and_range(too_high),
+            Err(BallisticsError::NoRealLanding { .. })
+        ));
+    }
+
+    #[test]
+    fn plot_window_extends_below_ground_and_behind_origin() {
+        let points = vec![(-20.0, 1.5), (10.0, 30.0), (60.0, -12.0)];
+        let ((x_min, x_max), (y_min, y_max)) = axis_bounds(&points);
+        assert!(x_min < -20.0 && x_max > 60.0);
+        assert!(y_min < -12.0 && y_max > 30.0);
+        assert_close((x_max - x_min) / (y_max - y_min), 2.0, 1e-9);
+    }
+
+    #[test]
+    fn f32_and_f64_cores_agree() {
+        for (angle, speed) in [(0.0, 10.0), (34.0, 56.0), (72.5, 480.0)] {
+            let (vx, vy) = launch_velocity(angle, speed);
+            let (vx32, vy32) = launch_velocity(angle as f32, speed as f32);
+            assert_close(f64::from(vx32), vx, 1e-4 * speed);
+            assert_close(f64::from(vy32), vy, 1e-4 * speed);
+        }
+
+        for (max_x, max_y) in [(0.2, 0.3), (120.0, 15.0), (40.0, 90.0)] {
+            let (x_span, y_span) = fixed_ratio_axis_window(max_x, max_y);
+            let (x32, y32) = fixed_ratio_axis_window(max_x as f32, max_y as f32);
+            assert_close(f64::from(x32), x_span, 1e-4);
+            assert_close(f64::from(y32), y_span, 1e-4);
+        }
+    }
+
+    #[test]
+    fn headless_replay_matches_prediction() {
+        let levels = Level::campaign(|| -0.8);
+        let level = Level::find(&levels, "earth 3").expect("campaign level");
+        let prediction =
+            simulate_prediction(level.default_launch, level, Integrator::SemiImplicitEuler);
+        assert_eq!(prediction.outcome, StepOutcome::HitTarget);
+
+        let mut projectile = launch_projectile(level.default_launch);
+        let mut outcome = StepOutcome::Flying;
+        let mut steps = 0;
+        while outcome == StepOutcome::Flying {
+            outcome = step_projectile(
+                &mut projectile,
+                level,
+                FIXED_STEP_S,
+                Integrator::SemiImplicitEuler,
+            );
+            steps += 1;
+        }
+        assert_eq!(outcome, prediction.outcome);
+        assert_eq!(steps + 1, prediction.points.len());
+        assert_eq!(projectile.position, *prediction.points.last().unwrap());
+
+        let calm = Level::campaign(|| 0.0);
+        let calm_level = Level::find(&calm, "EARTH 3").expect("campaign level");
+        let calm_prediction = simulate_prediction(
+            calm_level.default_launch,
+            calm_level,
+            Integrator::SemiImplicitEuler,
+        );
+        assert_eq!(calm_prediction.outcome, StepOutcome::HitGround);
+    }
+
+    #[test]
+    fn simulate_reports_level_shot_as_json() {
+        let levels = Level::campaign(|| -0.8);
+        let args: Vec<String> = ["--level", "earth 3", "--speed", "64"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let (level, config) = parse_simulate_args(&args, &levels).expect("valid arguments");
+        assert_eq!(level.code, "EARTH 3");
+        assert_eq!(config, level.default_launch);
+
+        let prediction = simulate_shot(
+            config,
+            &level,
+            Integrator::SemiImplicitEuler,
+            SHOT_MAX_STEPS,
+        );
+        let json = simulation_json(&level, config, &prediction);
+        let report: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+        assert_eq!(report["level"], "EARTH 3");
+        assert_eq!(report["outcome"], "hit_target", "{json}");
+        assert_eq!(report["bounces"], 0, "{json}");
+        let trajectory = report["trajectory"].as_array().expect("trajectory list");
+        assert_eq!(trajectory.len(), prediction.points.len());
+        assert_eq!(trajectory[0], serde_json::json!([0.0, 2.5]));
+
+        // Level codes are free text from the level files.
+        let mut quoted = level.clone();
+        quoted.code = r#"EARTH "3" \ custom"#.to_string();
+        let json = simulation_json(&quoted, config, &prediction);
+        let report: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+        assert_eq!(report["level"], quoted.code.as_str());
+
+        let unknown: Vec<String> = vec!["--level".to_string(), "MARS 1".to_string()];
+        assert!(parse_simulate_args(&unknown, &levels).is_err());
+    }
+
+    #[test]
+    fn solver_finds_replayable_wins_and_reports_unsolvable() {
+        let levels = Level::campaign(|| 0.8);
+        let level = Level::find(&levels, "EARTH 3").expect("campaign level");
+        let settings = SolverSettings {
+            angle_range_deg: (20.0, 40.0),
+            speed_range_mps: (50.0, 80.0),
+            height_range_m: (0.0, 5.0),
+            angle_steps: 5,
+            speed_steps: 7,
+            height_steps: 2,
+            refine_candidates: 2,
+            refine_iterations: 6,
+            ..SolverSettings::default()
+        };
+        let solutions = solve_level(level, &settings).expect("EARTH 3 is winnable");
+        assert!(
+            solutions
+                .windows(2)
+                .all(|pair| pair[0].miss_m <= pair[1].miss_m)
+        );
+        for solution in solutions.iter().take(3) {
+            let replay = simulate_prediction(solution.config, level, settings.integrator);
+            assert_eq!(replay.outcome, StepOutcome::HitTarget);
+            assert!(solution.miss_m <= 0.0);
+        }
+
+        let mut walled = level.clone();
+        walled
+            .barriers
+            .push(Barrier::Rect(Rect::new(100.0, 0.0, 10.0, 1000.0)));
+        let err = solve_level(&walled, &settings).expect_err("wall blocks every shot");
+        assert!(err.miss_m > 0.0);
+    }
+
+    #[test]
+    fn solver_counts_bank_shots_longer_than_the_preview() {
+        // MOON 4's bank shot flies for over 40 s, far past the 8 s preview budget.
+        let levels = Level::campaign(|| 0.0);
+        let level = Level::find(&levels, "MOON 4").expect("campaign level");
+        let settings = SolverSettings {
+            angle_range_deg: (12.0, 24.0),
+            speed_range_mps: (22.0, 30.0),
+            height_range_m: (310.0, 330.0),
+            angle_steps: 5,
+            speed_steps: 5,
+            height_steps: 3,
+            refine_candidates: 1,
+            refine_iterations: 4,
+            ..SolverSettings::default()
+        };
+        assert_eq!(settings.max_steps, SHOT_MAX_STEPS);
+        let solutions = solve_level(level, &settings).expect("MOON 4 is winnable");
+        let best = solutions[0];
+        assert!(best.flight_time_s > 8.0, "{best:?}");
+        let replay = simulate_shot(best.config, level, settings.integrator, settings.max_steps);
+        assert_eq!(replay.outcome, StepOutcome::HitTarget);
+    }
+
+    #[test]
+    fn hint_finds_a_nearby_winning_shot() {
+        let levels = Level::campaign(|| 0.0);
+        let level = Level::find(&levels, "EARTH 3").expect("campaign level");
+        let aim = level.default_launch;
+        assert_eq!(
+            simulate_prediction(aim, level, Integrator::SemiImplicitEuler).outcome,
+            StepOutcome::HitGround
+        );
+
+        let hint = nearest_solution(level, aim, Integrator::SemiImplicitEuler).expect("a win");
+        let replay = simulate_prediction(hint.config, level, Integrator::SemiImplicitEuler);
+        assert_eq!(replay.outcome, StepOutcome::HitTarget);
+        assert!((hint.config.angle_deg - aim.angle_deg).abs() <= 20.0);
+        assert!((hint.config.height_m - aim.height_m).abs() <= 40.0);
+
+        // Spread over frames, the search lands on the same hint.
+        let mut search = NearestSearch::new(level, aim, Integrator::SemiImplicitEuler);
+        let mut frames = 1;
+        let sliced = loop {
+            match search.advance(16) {
+                Some(result) => break result.expect("a win"),
+                None => frames += 1,
+            }
+        };
+        assert!(frames > 1);
+        assert_eq!(sliced, hint);
+    }
+
+    #[test]
+    fn builtin_level_files_load_in_manifest_order() {
+        let mut rolls = 0;
+        let levels = Level::campaign(|| {
+            rolls += 1;
+            0.5
+        });
+        assert_eq!(rolls, 4);
+        let codes: Vec<&str> = levels.iter().map(|level| level.code.as_str()).collect();
+        assert_eq!(
+            codes,
+            [
+                "EARTH 1", "EARTH 2", "EARTH 3", "EARTH 4", "MOON 1", "MOON 2", "MOON 3", "MOON 4"
+            ]
+        );
+        let moon_4 = &levels[7];
+        assert_eq!(moon_4.level_in_environment, 4);
+        assert_eq!(moon_4.environment.name, "Moon");
+        assert_eq!(moon_4.environment.wind_accel_x_mps2, 0.0);
+        assert_eq!(
+            moon_4.barriers[1],
+            Barrier::Rect(Rect::new(790.0, 340.0, 36.0, 260.0))
+        );
+        assert_eq!(moon_4.required_bounces, 1);
+        assert_eq!(levels[0].environment.wind_accel_x_mps2, 0.5);
+        assert_eq!(levels[0].environment.gravity_mps2, 9.8);
+    }
+
+    #[test]
+    fn malformed_level_files_report_file_and_reason() {
+        let good = builtin_campaign_files();
+        let replace = |file: &str, from: &str, to: &str| {
+            let mut files = good.clone();
+            let entry = files.iter_mut().find(|(name, _)| name == file).unwrap();
+            entry.1 = entry.1.replace(from, to);
+            parse_campaign(&files, || 0.0).expect_err("malformed campaign")
+        };
+
+        let errors = replace("earth_2.toml", "radius_m", "radius");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file, "earth_2.toml");
+        assert!(errors[0].to_string().contains("radius"), "{}", errors[0]);
+        let summary = errors[0].summary();
+        assert!(
+            !summary.contains('\n') && summary.contains("line"),
+            "{summary}"
+        );
+
+        let errors = replace("moon_2.toml", "restitution = 0.9", "restitution = 1.9");
+        assert!(errors[0].message.contains("restitution"), "{}", errors[0]);
+
+        let errors = replace("earth_1.toml", "\"random\"", "\"gusty\"");
+        assert!(errors[0].message.contains("gusty"), "{}", errors[0]);
+
+        let errors = replace("moon_1.toml", "MOON 1", "EARTH 1");
+        assert_eq!(errors[0].file, "moon_1.toml");
+        assert!(errors[0].message.contains("duplicate"), "{}", errors[0]);
+
+        assert!(parse_manifest("levels = []").is_err());
+    }
+
+    #[test]
+    fn shots_bounce_off_the_first_surface_they_reach() {
+        let wall = |x: f32, restitution: f32| BounceSurface {
+            restitution,
+            damping: DEFAULT_SURFACE_DAMPING,
+            ..slab(vec2(x + 1.0, 20.0), vec2(1.0, 20.0))
+        };
+        let mut level = open_range_level();
+        let config = LaunchConfig {
+            angle_deg: 0.0,
+            speed_mps: 50.0,
+            height_m: 20.0,
+        };
+
+        for surfaces in [
+            vec![wall(20.0, 0.5), wall(40.0, 1.0)],
+            vec![wall(40.0, 1.0), wall(20.0, 0.5)],
+        ] {
+            level.bounce_surfaces = surfaces;
+            let mut projectile = launch_projectile(config);
+            step_to_first_bounce(&mut projectile, &level);
+            assert!(projectile.position.x < 20.0, "{:?}", projectile.position);
+            assert!(
+                (-26.0..-24.0).contains(&projectile.velocity.x),
+                "{:?}",
+                projectile.velocity
+            );
+        }
+    }
+
+    #[test]
+    fn surface_friction_trades_glancing_speed_for_spin() {
+        let mut level = open_range_level();
+        let floor = |friction: f32| BounceSurface {
+            restitution: 0.6,
+            friction,
+            ..slab(vec2(200.0, 5.0), vec2(200.0, 5.0))
+        };
+        let config = LaunchConfig {
+            angle_deg: -10.0,
+            speed_mps: 40.0,
+            height_m: 14.0,
+        };
+        let mut first_bounce = |friction: f32| {
+            level.bounce_surfaces = vec![floor(friction)];
+            let mut projectile = launch_projectile(config);
+            let before = step_to_first_bounce(&mut projectile, &level);
+            (before.velocity, projectile)
+        };
+
+        let (incoming, smooth) = first_bounce(0.0);
+        assert!((smooth.velocity.x - incoming.x).abs() < 1e-3);
+        assert!((smooth.velocity.y + 0.6 * incoming.y).abs() < 0.1);
+        assert_eq!(smooth.angular_velocity_radps, 0.0);
+
+        // Light friction slows the slide and spins the ball backwards (clockwise).
+        let (_, grippy) = first_bounce(0.05);
+        assert!(grippy.velocity.x < incoming.x - 0.5);
+        assert!(grippy.angular_velocity_radps < 0.0);
+
+        // Enough friction stops the slip entirely: the ball leaves rolling.
+        let (_, rolling) = first_bounce(1.0);
+        let contact_speed =
+            rolling.velocity.x + PROJECTILE_RADIUS_M * rolling.angular_velocity_radps;
+        assert!(contact_speed.abs() < 1e-3, "{rolling:?}");
+        assert!((rolling.velocity.x - incoming.x * 5.0 / 7.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn fast_shots_cannot_tunnel_through_thin_obstacles() {
+        let mut level = open_range_level();
+        let config = LaunchConfig {
+            angle_deg: 0.0,
+            speed_mps: 400.0,
+            height_m: 20.0,
+        };
+        let thin_obstacles = [
+            Barrier::Rect(Rect::new(50.7, 0.0, 0.2, 100.0)),
+            Barrier::Circle {
+                center: vec2(50.8, 20.0),
+                radius_m: 0.3,
+            },
+            Barrier::Polygon(vec![vec2(50.7, 0.0), vec2(50.9, 0.0), vec2(50.8, 60.0)]),
+        ];
+        for obstacle in thin_obstacles {
+            level.barriers = vec![obstacle.clone()];
+            let shot = simulate_shot(
+                config,
+                &level,
+                Integrator::SemiImplicitEuler,
+                SHOT_MAX_STEPS,
+            );
+            assert_eq!(shot.outcome, StepOutcome::HitBarrier, "{obstacle:?}");
+            // Steps are ~1.7 m long, so no step ends inside; the shot stops at contact.
+            let (contact, flight) = shot.points.split_last().unwrap();
+            assert!(!flight.iter().any(|p| obstacle.contains(*p)));
+            assert!((contact.x - 50.7).abs() < 0.25, "{contact:?}");
+        }
+    }
+
+    #[test]
+    fn hits_between_steps_stop_at_the_exact_contact() {
+        let mut level = open_range_level();
+        level.environment.gravity_mps2 = 1e-6;
+        level.target.center = vec2(50.8, 20.0);
+        level.target.radius_m = 0.3;
+        let config = LaunchConfig {
+            angle_deg: 0.0,
+            speed_mps: 500.0,
+            height_m: 20.0,
+        };
+
+        // Steps are ~2.1 m long; the target sits between two of them.
+        let shot = simulate_shot(
+            config,
+            &level,
+            Integrator::SemiImplicitEuler,
+            SHOT_MAX_STEPS,
+        );
+        assert_eq!(shot.outcome, StepOutcome::HitTarget);
+        let hit = *shot.points.last().unwrap();
+        assert!((hit.x - 50.5).abs() < 1e-3, "{hit:?}");
+        assert!((shot.flight_time_s - 50.5 / 500.0).abs() < 1e-5);
+
+        level.barriers = vec![Barrier::Rect(Rect::new(50.2, 0.0, 0.1, 40.0))];
+        let shot = simulate_shot(
+            config,
+            &level,
+            Integrator::SemiImplicitEuler,
+            SHOT_MAX_STEPS,
+        );
+        assert_eq!(shot.outcome, StepOutcome::HitBarrier);
+        assert!((shot.points.last().unwrap().x - 50.2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn bounce_steps_sweep_the_legs_into_and_off_the_surface() {
+        let mut level = open_range_level();
+        level.environment.gravity_mps2 = 1e-6;
+        level.barriers.clear();
+        level.bounce_surfaces = vec![slab(vec2(200.0, 5.0), vec2(200.0, 5.0))];
+        // Steps are ~1.7 m long and the floor is met halfway through one.
+        let config = LaunchConfig {
+            angle_deg: -30.0,
+            speed_mps: 400.0,
+            height_m: 10.0 + (400.0 * FIXED_STEP_S * 0.5 * 20.5),
+        };
+        let mut projectile = launch_projectile(config);
+        let before = step_to_first_bounce(&mut projectile, &level);
+        let direction = before.velocity;
+        let contact = before.position + (direction * ((10.0 - before.position.y) / direction.y));
+        let chord_middle = (before.position + projectile.position) * 0.5;
+        assert!(
+            chord_middle.y > contact.y + 0.3,
+            "{chord_middle:?} {contact:?}"
+        );
+
+        let shot_into = |center: Vec2| {
+            let mut level = level.clone();
+            level.barriers = vec![Barrier::Circle {
+                center,
+                radius_m: 0.1,
+            }];
+            simulate_prediction(config, &level, Integrator::SemiImplicitEuler)
+        };
+        // The straight line from before the bounce to after it skims over the contact.
+        assert_ne!(shot_into(chord_middle).outcome, StepOutcome::HitBarrier);
+        let grazed = shot_into(contact + vec2(0.0, 0.05));
+        assert_eq!(grazed.outcome, StepOutcome::HitBarrier);
+        assert_eq!(grazed.bounces, 0);
+        assert!(grazed.points.last().unwrap().distance(contact) < 0.2);
+    }
+
+    #[test]
+    fn moving_targets_are_hit_where_they_will_be() {
+        let mut level = open_range_level();
+        level.environment.gravity_mps2 = 1e-6;
+        level.barriers.clear();
+        level.target.center = vec2(60.0, 20.0);
+        level.target.radius_m = 2.0;
+        level.target.motion = TargetMotion::Linear {
+            offset: vec2(0.0, 40.0),
+            period_s: 0.2,
+            phase: 0.0,
+        };
+        let flat = |height_m: f32| LaunchConfig {
+            angle_deg: 0.0,
+            speed_mps: 500.0,
+            height_m,
+        };
+
+        // Aimed at where the target starts, the shot arrives after it has risen.
+        let at_start = simulate_prediction(flat(20.0), &level, Integrator::SemiImplicitEuler);
+        assert_ne!(at_start.outcome, StepOutcome::HitTarget);
+        let arrival = level.target.position_at(60.0 / 500.0);
+        assert!(arrival.y > 50.0, "{arrival:?}");
+        let led = simulate_prediction(flat(arrival.y), &level, Integrator::SemiImplicitEuler);
+        assert_eq!(led.outcome, StepOutcome::HitTarget);
+
+        let settings = SolverSettings {
+            angle_range_deg: (-2.0, 2.0),
+            speed_range_mps: (499.0, 501.0),
+            height_range_m: (0.0, 80.0),
+            angle_steps: 3,
+            speed_steps: 1,
+            height_steps: 21,
+            ..SolverSettings::default()
+        };
+        let solutions = solve_level(&level, &settings).expect("the solver leads the target");
+        let replay = simulate_prediction(solutions[0].config, &level, settings.integrator);
+        assert_eq!(replay.outcome, StepOutcome::HitTarget);
+    }
+
+    #[test]
+    fn spline_phase_shifts_the_start_along_the_path() {
+        let mut target = Level::campaign(|| 0.0)[0].target.clone();
+        let center = target.center;
+        target.motion = TargetMotion::Spline {
+            waypoints: vec![center + vec2(20.0, 10.0), center + vec2(30.0, -4.0)],
+            period_s: 5.0,
+            phase: 0.5,
+        };
+        // Phase 0.5 starts the spline halfway round; it still passes its waypoints.
+        assert!(target.position_at(-2.5).distance(center) < 1e-3);
+        assert!(
+            target
+                .position_at(5.0 * 2.0 / 3.0 - 2.5)
+                .distance(center + vec2(30.0, -4.0))
+                < 1e-3
+        );
+    }
+
+    #[test]
+    fn moving_surfaces_hand_their_velocity_to_the_shot() {
+        let mut level = open_range_level();
+        level.barriers.clear();
+        let gravity = level.environment.gravity_mps2;
+        let pad = |center: Vec2, motion: SurfaceMotion| BounceSurface {
+            motion,
+            ..slab(center, vec2(40.0, 0.5))
+        };
+        let drop = LaunchConfig {
+            angle_deg: -90.0,
+            speed_mps: 5.0,
+            height_m: 30.0,
+        };
+        // Vertical speed just before and just after the first bounce, and when.
+        let rebound = |level: &Level| {
+            let mut projectile = launch_projectile(drop);
+            let before = step_to_first_bounce(&mut projectile, level);
+            let incoming = before.velocity.y - (gravity * FIXED_STEP_S);
+            (incoming, projectile.velocity, projectile.elapsed_s)
+        };
+
+        level.bounce_surfaces = vec![pad(vec2(0.0, 10.0), SurfaceMotion::Fixed)];
+        let (_, fixed_out, _) = rebound(&level);
+        let lift = SurfaceMotion::Slide {
+            offset: vec2(0.0, 15.0),
+            period_s: 4.0,
+        };
+        level.bounce_surfaces = vec![pad(vec2(0.0, 10.0), lift)];
+        let (incoming, out, hit_s) = rebound(&level);
+        let lifting = level.bounce_surfaces[0].velocity_at(Vec2::ZERO, hit_s).y;
+        assert!(lifting > 1.0, "{lifting}");
+        // Restitution applies to the speed relative to the rising platform.
+        assert!(
+            ((out.y - lifting) + (0.5 * (incoming - lifting))).abs() < 0.1,
+            "{incoming} {out:?} {lifting}"
+        );
+        assert!(out.y > fixed_out.y + 1.0);
+
+        // Left of the pivot, a clockwise paddle is rising when it meets the shot and
+        // a counter-clockwise one is falling away.
+        let spin = |angular_velocity_radps: f32| {
+            let mut level = level.clone();
+            level.bounce_surfaces = vec![pad(
+                vec2(10.0, 10.0),
+                SurfaceMotion::Spin {
+                    angular_velocity_radps,
+                },
+            )];
+            level
+        };
+        let (_, clockwise, _) = rebound(&spin(-0.2));
+        let (_, counter, _) = rebound(&spin(0.2));
+        assert!(clockwise.y > counter.y + 3.0, "{clockwise:?} {counter:?}");
+
+        // Motion is a function of flight time alone, so replays match step for step.
+        let level = spin(-0.2);
+        let prediction = simulate_prediction(drop, &level, Integrator::SemiImplicitEuler);
+        let mut projectile = launch_projectile(drop);
+        let mut points = vec![projectile.position];
+        while points.len() < prediction.points.len() {
+            step_projectile(
+                &mut projectile,
+                &level,
+                FIXED_STEP_S,
+                Integrator::SemiImplicitEuler,
+            );
+            points.push(projectile.position);
+        }
+        assert_eq!(points, prediction.points);
+    }
+
+    #[test]
+    fn fast_moving_surfaces_bounce_once_per_contact() {
+        let mut level = open_range_level();
+        level.barriers.clear();
+        let pad = |center_x: f32, motion: SurfaceMotion| BounceSurface {
+            motion,
+            ..slab(vec2(center_x, 10.0), vec2(40.0, 0.5))
+        };
+        let drop = LaunchConfig {
+            angle_deg: -90.0,
+            speed_mps: 5.0,
+            height_m: 30.0,
+        };
+        // Step numbers at which the shot bounced, until it resolves.
+        let bounce_steps = |level: &Level| {
+            let mut projectile = launch_projectile(drop);
+            let mut steps = Vec::new();
+            for step in 0..SHOT_MAX_STEPS {
+                let bounces = projectile.bounces;
+                let outcome = step_projectile(
+                    &mut projectile,
+                    level,
+                    FIXED_STEP_S,
+                    Integrator::SemiImplicitEuler,
+                );
+                if projectile.bounces > bounces {
+                    steps.push(step);
+                }
+                if outcome != StepOutcome::Flying {
+                    break;
+                }
+            }
+            steps
+        };
+
+        // A 30 m lift once a second, and 90 deg/s paddles swinging up into the shot
+        // from either side.
+        for (center_x, motion) in [
+            (
+                0.0,
+                SurfaceMotion::Slide {
+                    offset: vec2(0.0, 30.0),
+                    period_s: 1.0,
+                },
+            ),
+            (
+                10.0,
+                SurfaceMotion::Spin {
+                    angular_velocity_radps: -std::f32::consts::FRAC_PI_2,
+                },
+            ),
+            (
+                -10.0,
+                SurfaceMotion::Spin {
+                    angular_velocity_radps: std::f32::consts::FRAC_PI_2,
+                },
+            ),
+        ] {
+            level.bounce_surfaces = vec![pad(center_x, motion)];
+            let steps = bounce_steps(&level);
+            assert!(!steps.is_empty(), "{motion:?} never bounced");
+            // A surface chasing the shot may meet it again, but not 0.1 s later.
+            assert!(
+                steps.windows(2).all(|pair| pair[1] - pair[0] > 24),
+                "{motion:?} bounced at steps {steps:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn paddles_that_sweep_over_a_slow_ball_bat_it_away_once() {
+        let mut level = open_range_level();
+        level.barriers.clear();
+        // A clockwise paddle whose left arm swings up through a ball tossed from inside it.
+        level.bounce_surfaces = vec![BounceSurface {
+            motion: SurfaceMotion::Spin {
+                angular_velocity_radps: -1.0,
+            },
+            ..slab(vec2(10.0, 20.0), vec2(40.0, 0.5))
+        }];
+        let paddle = &level.bounce_surfaces[0];
+        let mut projectile = launch_projectile(LaunchConfig {
+            angle_deg: 90.0,
+            speed_mps: 0.1,
+            height_m: 20.2,
+        });
+
+        step_projectile(
+            &mut projectile,
+            &level,
+            FIXED_STEP_S,
+            Integrator::SemiImplicitEuler,
+        );
+        assert_eq!(projectile.bounces, 1);
+        let hit_s = projectile.elapsed_s;
+        let paddle_velocity = paddle.velocity_at(projectile.position, hit_s);
+        let normal = paddle.to_world(vec2(0.0, 1.0), hit_s) - paddle.to_world(Vec2::ZERO, hit_s);
+        // The arm is moving at ~10 m/s where it meets the ball and passes that on: the
+        // ball leaves at (1 + restitution) times the arm's speed along the normal.
+        assert!(paddle_velocity.y > 9.0, "{paddle_velocity:?}");
+        assert!(
+            (projectile.velocity.dot(normal) - (1.5 * paddle_velocity.dot(normal))).abs() < 0.1,
+            "{projectile:?} {paddle_velocity:?}"
+        );
+
+        for _ in 0..48 {
+            step_projectile(
+                &mut projectile,
+                &level,
+                FIXED_STEP_S,
+                Integrator::SemiImplicitEuler,
+            );
+        }
+        assert_eq!(projectile.bounces, 1);
+        assert!(
+            paddle.to_local(projectile.position, projectile.elapsed_s).y > 0.5,
+            "{projectile:?}"
+        );
+    }
+
+    #[test]
+    fn ballistics_errors_get_distinct_exit_codes() {
+        let errors = [
+            BallisticsError::NonFiniteInput {
+                field: "speed_mps",
+                value: f64::NAN,
+            },
+            BallisticsError::NegativeSpeed { speed_mps: -1.0 },
+            BallisticsError::NonPositiveGravity { gravity_mps2: 0.0 },
+            BallisticsError::NoRealLanding { discriminant: -1.0 },
+            BallisticsError::OutOfReach {
+                speed_mps: 1.0,
+                target_x_m: 100.0,
+                target_y_m: 0.0,
+            },
+        ];
+        let mut codes: Vec<i32> = errors
+            .into_iter()
+            .map(|err| CliError::from(err).exit_code())
+            .collect();
+        codes.push(CliError::Input(String::new()).exit_code());
+        let count = codes.len();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), count);
     }
 
     #[test]
@@ -307,6 +2508,7 @@ mod tests {
             angle_deg: 45.0,
             speed_mps: 30.0,
             height_m: 1.5,
+            ..Inputs::default()
         };
         let (time, _) = flight_time_and_range(inputs).expect("calculation should succeed");
         let points = sample_trajectory(inputs, time, 100);
@@ -324,6 +2526,7 @@ mod tests {
             angle_deg: 60.0,
             speed_mps: 25.0,
             height_m: 10.0,
+            ..Inputs::default()
         };
         let (time, _) = flight_time_and_range(inputs).expect("calculation should succeed");
         let points = sample_trajectory(inputs, time, 100);
@@ -339,6 +2542,7 @@ mod tests {
             angle_deg: 45.0,
             speed_mps: 20.0,
             height_m: 3.0,
+            ..Inputs::default()
         };
         let (time, _) = flight_time_and_range(inputs).expect("calculation should succeed");
         let points = sample_trajectory(inputs, time, 100);
@@ -0,0 +1,38 @@
+//! Proves every built-in campaign level can be won, the way `solve-level` does:
+//! the full solver grid, every Earth wind the CLI samples, the game's shot budget.
+//! Slow, so it is ignored by default; run it with
+//! `cargo test --release --test campaign_solvable -- --ignored`.
+
+use parabolic_rust::core::campaign::earth_wind_samples;
+use parabolic_rust::core::integrate::Integrator;
+use parabolic_rust::core::sim::{Level, SHOT_MAX_STEPS, StepOutcome, simulate_shot};
+use parabolic_rust::core::solver::{SolverSettings, solve_level};
+
+/// Winds per direction, matching the CLI's `solve-level`.
+const WINDS_PER_DIRECTION: usize = 3;
+
+#[test]
+#[ignore = "exhaustive solve of the whole campaign; run with --ignored in release"]
+fn every_builtin_level_is_solvable_within_the_shot_budget() {
+    let settings = SolverSettings::default();
+    assert_eq!(settings.max_steps, SHOT_MAX_STEPS);
+
+    let mut solved: Vec<Level> = Vec::new();
+    for wind in earth_wind_samples(WINDS_PER_DIRECTION) {
+        for level in Level::campaign(|| wind) {
+            if solved.contains(&level) {
+                continue;
+            }
+            let solutions = solve_level(&level, &settings)
+                .unwrap_or_else(|err| panic!("{} (wind {wind}): {err}", level.code));
+            let replay = simulate_shot(
+                solutions[0].config,
+                &level,
+                Integrator::SemiImplicitEuler,
+                settings.max_steps,
+            );
+            assert_eq!(replay.outcome, StepOutcome::HitTarget, "{}", level.code);
+            solved.push(level);
+        }
+    }
+}